@@ -1,15 +1,22 @@
 use std::str::FromStr;
 
+use nom::branch::alt;
 use nom::bytes::complete::take;
-use nom::bytes::complete::{tag, take_till, take_while};
+use nom::bytes::complete::{tag, take_till};
 use nom::character::complete::line_ending;
-use nom::combinator::{map, map_res, opt, peek};
-use nom::IResult;
+use nom::combinator::{map, map_res, opt, peek, recognize};
+use nom::multi::separated_list1;
+use nom::sequence::delimited;
+use nom::{IResult, InputTake as _};
 use nom_locate::LocatedSpan;
 
-use crate::value::{Date, Time, Value, ValueType};
+use crate::lines::unfold;
+use crate::recur::RecurrenceRule;
+use crate::value::{Date, Duration, Time, Value, ValueType};
 
-type Span<'a> = LocatedSpan<&'a str>;
+pub mod owned;
+
+pub type Span<'a> = LocatedSpan<&'a str>;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub enum SyntaxKind {
@@ -17,7 +24,9 @@ pub enum SyntaxKind {
     Parameter,
 }
 
-#[derive(Debug, strum::EnumIter)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter, serde::Serialize, serde::Deserialize,
+)]
 pub enum PropertyName {
     // Meta properties, probably should be removed once this looks more like the nested structure
     Begin,
@@ -188,7 +197,9 @@ impl FromStr for PropertyName {
     }
 }
 
-#[derive(Debug, strum::EnumIter)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter, serde::Serialize, serde::Deserialize,
+)]
 pub enum ParameterName {
     AltRep,
     CN,
@@ -276,6 +287,46 @@ pub struct Parameter<'a> {
     pub value: Option<Span<'a>>,
 }
 
+impl Parameter<'_> {
+    /// Check each of the parameter's values against its type.
+    pub fn check_value_type(&self) -> Result<(), String> {
+        match self.name {
+            Some(name) => self.check_values(name.to_parameter().value_type()),
+            None => Ok(()),
+        }
+    }
+
+    /// Check each of the parameter's values against the given type.
+    pub fn check_values(&self, value_type: ValueType) -> Result<(), String> {
+        let Some(value) = self.value else {
+            return Ok(());
+        };
+        let value = unfold(value.fragment());
+        split_quoted(&value)
+            .into_iter()
+            .try_for_each(|v| check_value(value_type, v.trim_matches('"')))
+    }
+}
+
+/// Split a parameter value into its comma separated values, ignoring commas in quotes.
+fn split_quoted(s: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                values.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(&s[start..]);
+    values
+}
+
 #[derive(Debug)]
 pub struct Property<'a> {
     pub name_raw: Span<'a>,
@@ -285,57 +336,93 @@ pub struct Property<'a> {
 }
 
 impl<'a> Property<'a> {
+    pub fn param(&self, name: ParameterName) -> Option<&Parameter<'a>> {
+        self.params.iter().find(|p| p.name == Some(name))
+    }
+
+    /// The type of the value, taking into account any `VALUE` parameter overriding the default
+    /// type for the property.
+    pub fn value_type(&self) -> Option<ValueType> {
+        let explicit = self
+            .param(ParameterName::Value)
+            .and_then(|p| p.value)
+            .and_then(|v| ValueType::from_str(v.fragment()).ok());
+        explicit.or_else(|| self.name.map(|n| n.to_property().value_type()))
+    }
+
+    pub fn parse_value(&self) -> Option<Value> {
+        let (_, value) = parse_value(self.value?, self.value_type()?).ok()?;
+        Some(value)
+    }
+
     pub fn check_value_type(&self) -> Result<(), String> {
         let Some(value) = self.value else {
             return Ok(());
         };
-        let Some(name) = &self.name else {
+        let Some(value_type) = self.value_type() else {
             return Ok(());
         };
-        let value_raw = value.fragment();
-        match name.to_property().value_type() {
-            crate::value::ValueType::Binary => Ok(()),
-            crate::value::ValueType::Boolean => {
-                if matches!(value_raw.to_lowercase().as_str(), "true" | "false") {
-                    Ok(())
-                } else {
-                    Err("Did not match \"true\" or \"false\"".to_owned())
-                }
-            }
-            crate::value::ValueType::CalAddress => {
-                if !value_raw.starts_with("mailto:") {
-                    return Err("Does not start with \"mailto:\"".to_owned());
-                }
-                if !value_raw.trim_start_matches("mailto:").contains("@") {
-                    return Err("Does not contain '@'".to_owned());
-                }
+        check_value(value_type, &unfold(value.fragment()))
+    }
+}
+
+pub fn check_value(value_type: ValueType, value_raw: &str) -> Result<(), String> {
+    match value_type {
+        ValueType::Binary => Ok(()),
+        ValueType::Boolean => {
+            if matches!(value_raw.to_lowercase().as_str(), "true" | "false") {
                 Ok(())
+            } else {
+                Err("Did not match \"true\" or \"false\"".to_owned())
             }
-            crate::value::ValueType::Date => check_date_type(value_raw),
-            crate::value::ValueType::DateTime => {
-                let Some((date, time)) = value_raw.split_once('T') else {
-                    return Err("Did not contain 'T'".to_owned());
-                };
-                check_date_type(date)?;
-                check_time_type(time)
-            }
-            crate::value::ValueType::Duration => Ok(()),
-            crate::value::ValueType::Float => f64::from_str(value_raw)
-                .map(|_| ())
-                .map_err(|e| e.to_string()),
-            crate::value::ValueType::Integer => i64::from_str(value_raw)
-                .map(|_| ())
-                .map_err(|e| e.to_string()),
-            crate::value::ValueType::PeriodOfTime => Ok(()),
-            crate::value::ValueType::RecurrenceRule => Ok(()),
-            crate::value::ValueType::Text => Ok(()),
-            crate::value::ValueType::Time => check_time_type(value_raw),
-            crate::value::ValueType::Uri => Ok(()),
-            crate::value::ValueType::UtcOffset => Ok(()),
         }
+        ValueType::CalAddress => check_cal_address(value_raw),
+        ValueType::Date => check_date_type(value_raw),
+        ValueType::DateTime => {
+            let Some((date, time)) = value_raw.split_once('T') else {
+                return Err("Did not contain 'T'".to_owned());
+            };
+            check_date_type(date)?;
+            check_time_type(time)
+        }
+        ValueType::Duration => Duration::from_str(value_raw).map(|_| ()),
+        ValueType::Float => f64::from_str(value_raw)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        ValueType::Integer => i64::from_str(value_raw)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        ValueType::PeriodOfTime => Ok(()),
+        ValueType::RecurrenceRule => RecurrenceRule::from_str(value_raw).map(|_| ()),
+        ValueType::Text => Ok(()),
+        ValueType::Time => check_time_type(value_raw),
+        ValueType::Uri => check_uri(value_raw).map(|_| ()),
+        ValueType::UtcOffset => Ok(()),
     }
 }
 
+fn check_uri(s: &str) -> Result<uriparse::URI<'_>, String> {
+    uriparse::URI::try_from(s).map_err(|e| format!("Invalid URI: {e}"))
+}
+
+fn check_cal_address(s: &str) -> Result<(), String> {
+    let uri = check_uri(s)?;
+    if !uri.scheme().as_str().eq_ignore_ascii_case("mailto") {
+        return Ok(());
+    }
+    let path = uri.path().to_string();
+    let Some((local, domain)) = path.split_once('@') else {
+        return Err("The mailto address is missing '@'".to_owned());
+    };
+    if local.is_empty() {
+        return Err("The mailto address is missing the local part before '@'".to_owned());
+    }
+    if domain.is_empty() {
+        return Err("The mailto address is missing the domain after '@'".to_owned());
+    }
+    Ok(())
+}
+
 fn check_date_type(s: &str) -> Result<(), String> {
     if s.len() != 8 {
         return Err("Length was not 8".to_owned());
@@ -359,19 +446,352 @@ fn check_time_type(s: &str) -> Result<(), String> {
     Ok(())
 }
 
-pub fn parse_properties(s: Span) -> IResult<Span, Vec<Property>> {
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, serde::Serialize, serde::Deserialize)]
+pub enum SyntaxErrorKind {
+    #[error("Missing ':' before the property value")]
+    MissingColon,
+    #[error("Missing property name")]
+    EmptyName,
+    #[error("Missing parameter name")]
+    EmptyParameterName,
+    #[error("Parameter is missing '=' and a value")]
+    MissingParameterValue,
+    #[error("Parameter value is empty")]
+    EmptyParameterValue,
+    #[error("Illegal character {0:?} in name, only letters, digits and '-' are allowed")]
+    IllegalNameCharacter(char),
+    #[error("Illegal character {0:?} in parameter value")]
+    IllegalParameterValueCharacter(char),
+    #[error("Control character {0:?} in value")]
+    ControlCharacter(char),
+    #[error("Replacement character U+FFFD in value, the file may not be valid UTF-8")]
+    ReplacementCharacter,
+    #[error("Carriage return without a line feed")]
+    BareCarriageReturn,
+    #[error("Unexpected characters")]
+    UnexpectedCharacters,
+    #[error("Missing END:{0} for this component")]
+    MissingEnd(String),
+    #[error("END:{0} without a matching BEGIN")]
+    UnmatchedEnd(String),
+}
+
+#[derive(Debug)]
+pub struct SyntaxError<'a> {
+    pub span: Span<'a>,
+    pub kind: SyntaxErrorKind,
+}
+
+/// A component delimited by `BEGIN` and `END` properties, referring to its properties by their
+/// index in the [`Document`].
+#[derive(Debug)]
+pub struct Component<'a> {
+    pub name: Span<'a>,
+    pub begin: usize,
+    pub end: Option<usize>,
+    pub properties: Vec<usize>,
+    pub children: Vec<Component<'a>>,
+}
+
+impl<'a> Component<'a> {
+    pub fn is(&self, name: &str) -> bool {
+        self.name.fragment().eq_ignore_ascii_case(name)
+    }
+
+    pub fn properties<'d>(
+        &'d self,
+        document: &'d Document<'a>,
+    ) -> impl Iterator<Item = &'d Property<'a>> + 'd {
+        self.properties.iter().map(|&i| &document.properties[i])
+    }
+
+    pub fn property<'d>(
+        &'d self,
+        document: &'d Document<'a>,
+        name: PropertyName,
+    ) -> Option<&'d Property<'a>> {
+        self.properties(document).find(|p| p.name == Some(name))
+    }
+
+    /// The index of the last property of the component, its `END` if it has one.
+    pub fn last(&self) -> usize {
+        self.end.unwrap_or_else(|| {
+            let property = self.properties.last().copied();
+            let child = self.children.last().map(Component::last);
+            property.max(child).unwrap_or(self.begin)
+        })
+    }
+
+    /// All properties between the `BEGIN` and `END`, including those of nested components.
+    pub fn descendant_properties<'d>(&self, document: &'d Document<'a>) -> &'d [Property<'a>] {
+        let end = self.end.unwrap_or(self.last() + 1);
+        &document.properties[self.begin + 1..end]
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Document<'a> {
+    pub properties: Vec<Property<'a>>,
+    pub components: Vec<Component<'a>>,
+    pub errors: Vec<SyntaxError<'a>>,
+}
+
+impl<'a> Document<'a> {
+    /// All of the components in the document, parents before their children.
+    pub fn all_components(&self) -> Vec<&Component<'a>> {
+        fn walk<'c, 'a>(component: &'c Component<'a>, out: &mut Vec<&'c Component<'a>>) {
+            out.push(component);
+            for child in &component.children {
+                walk(child, out);
+            }
+        }
+        let mut out = Vec::new();
+        for component in &self.components {
+            walk(component, &mut out);
+        }
+        out
+    }
+}
+
+/// Parse a whole document, recovering from malformed content lines by recording a syntax error
+/// and carrying on from the next line.
+pub fn parse_document(s: Span) -> Document {
+    let mut document = Document::default();
     let mut sc = s;
-    let mut properties = Vec::new();
     while !sc.is_empty() {
-        let (s, p) = parse_property(sc)?;
-        sc = s;
-        properties.push(p);
+        if let Ok((s, line_break)) = parse_line_break(sc) {
+            // blank line
+            check_line_break(line_break, &mut document.errors);
+            sc = s;
+            continue;
+        }
+        let (_, line) = take_line(sc);
+        let s = match parse_property(sc) {
+            Ok((s, property)) => {
+                check_property(&property, &mut document.errors);
+                let (s, rest) = take_line(s);
+                if !rest.is_empty() {
+                    document.errors.push(SyntaxError {
+                        span: rest,
+                        kind: SyntaxErrorKind::UnexpectedCharacters,
+                    });
+                } else if property.value.is_none() {
+                    // point at where the colon should be
+                    document.errors.push(SyntaxError {
+                        span: rest,
+                        kind: SyntaxErrorKind::MissingColon,
+                    });
+                }
+                document.properties.push(property);
+                s
+            }
+            Err(_) => {
+                document.errors.push(SyntaxError {
+                    span: line,
+                    kind: SyntaxErrorKind::UnexpectedCharacters,
+                });
+                take_line(sc).0
+            }
+        };
+        sc = match parse_line_break(s) {
+            Ok((s, line_break)) => {
+                check_line_break(line_break, &mut document.errors);
+                s
+            }
+            Err(_) => s,
+        };
     }
-    Ok((sc, properties))
+    document.components = build_components(&document.properties, &mut document.errors);
+    document
+}
+
+/// Nest the properties into components, matching up the `BEGIN` and `END` properties.
+fn build_components<'a>(
+    properties: &[Property<'a>],
+    errors: &mut Vec<SyntaxError<'a>>,
+) -> Vec<Component<'a>> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<Component<'a>> = Vec::new();
+    let close =
+        |component: Component<'a>, stack: &mut Vec<Component<'a>>, roots: &mut Vec<_>| match stack
+            .last_mut()
+        {
+            Some(parent) => parent.children.push(component),
+            None => roots.push(component),
+        };
+    for (i, property) in properties.iter().enumerate() {
+        match (property.name, property.value) {
+            (Some(PropertyName::Begin), Some(name)) => stack.push(Component {
+                name,
+                begin: i,
+                end: None,
+                properties: Vec::new(),
+                children: Vec::new(),
+            }),
+            (Some(PropertyName::End), Some(name)) => {
+                let Some(open) = stack.iter().rposition(|c| c.is(name.fragment())) else {
+                    errors.push(SyntaxError {
+                        span: name,
+                        kind: SyntaxErrorKind::UnmatchedEnd(name.fragment().to_string()),
+                    });
+                    continue;
+                };
+                while stack.len() > open + 1 {
+                    let unclosed = stack.pop().unwrap();
+                    errors.push(SyntaxError {
+                        span: unclosed.name,
+                        kind: SyntaxErrorKind::MissingEnd(unclosed.name.fragment().to_string()),
+                    });
+                    close(unclosed, &mut stack, &mut roots);
+                }
+                let mut component = stack.pop().unwrap();
+                component.end = Some(i);
+                close(component, &mut stack, &mut roots);
+            }
+            _ => {
+                if let Some(component) = stack.last_mut() {
+                    component.properties.push(i);
+                }
+            }
+        }
+    }
+    while let Some(unclosed) = stack.pop() {
+        errors.push(SyntaxError {
+            span: unclosed.name,
+            kind: SyntaxErrorKind::MissingEnd(unclosed.name.fragment().to_string()),
+        });
+        close(unclosed, &mut stack, &mut roots);
+    }
+    roots
+}
+
+fn check_line_break<'a>(line_break: Span<'a>, errors: &mut Vec<SyntaxError<'a>>) {
+    if *line_break.fragment() == "\r" {
+        errors.push(SyntaxError {
+            span: line_break,
+            kind: SyntaxErrorKind::BareCarriageReturn,
+        });
+    }
+}
+
+/// Check the finer details of a property that the parser is lenient about.
+fn check_property<'a>(property: &Property<'a>, errors: &mut Vec<SyntaxError<'a>>) {
+    if property.name_raw.is_empty() {
+        errors.push(SyntaxError {
+            span: property.name_raw,
+            kind: SyntaxErrorKind::EmptyName,
+        });
+    }
+    check_name(property.name_raw, errors);
+    for param in &property.params {
+        if param.name_raw.is_empty() {
+            errors.push(SyntaxError {
+                span: param.name_raw,
+                kind: SyntaxErrorKind::EmptyParameterName,
+            });
+        }
+        check_name(param.name_raw, errors);
+        match param.value {
+            None => errors.push(SyntaxError {
+                span: param.name_raw,
+                kind: SyntaxErrorKind::MissingParameterValue,
+            }),
+            Some(value) if value.is_empty() => errors.push(SyntaxError {
+                span: value,
+                kind: SyntaxErrorKind::EmptyParameterValue,
+            }),
+            Some(value) => {
+                let quoted = value.fragment().len() >= 2 && value.starts_with('"');
+                check_chars(value, errors, |i, c| {
+                    if c.is_control() && c != '\t' {
+                        Some(SyntaxErrorKind::ControlCharacter(c))
+                    } else if c == '"' && !(quoted && (i == 0 || i == value.len() - 1)) {
+                        Some(SyntaxErrorKind::IllegalParameterValueCharacter(c))
+                    } else {
+                        None
+                    }
+                });
+            }
+        }
+    }
+    if let Some(value) = property.value {
+        let fragment = value.fragment();
+        check_chars(value, errors, |i, c| match c {
+            // line folding
+            '\r' if fragment[i..].starts_with("\r\n ") || fragment[i..].starts_with("\r\n\t") => {
+                None
+            }
+            '\n' if i > 0 && fragment[..i].ends_with('\r') => None,
+            '\n' if fragment[i..].starts_with("\n ") || fragment[i..].starts_with("\n\t") => None,
+            '\u{FFFD}' => Some(SyntaxErrorKind::ReplacementCharacter),
+            c if c.is_control() && c != '\t' => Some(SyntaxErrorKind::ControlCharacter(c)),
+            _ => None,
+        });
+    }
+}
+
+fn check_name<'a>(name: Span<'a>, errors: &mut Vec<SyntaxError<'a>>) {
+    check_chars(name, errors, |_, c| {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            None
+        } else {
+            Some(SyntaxErrorKind::IllegalNameCharacter(c))
+        }
+    });
+}
+
+/// Record an error for every character in the span that `check` finds a problem with.
+fn check_chars<'a>(
+    span: Span<'a>,
+    errors: &mut Vec<SyntaxError<'a>>,
+    check: impl Fn(usize, char) -> Option<SyntaxErrorKind>,
+) {
+    for (i, c) in span.fragment().char_indices() {
+        if let Some(kind) = check(i, c) {
+            let (rest, _) = span.take_split(i);
+            let (_, span) = rest.take_split(c.len_utf8());
+            errors.push(SyntaxError { span, kind });
+        }
+    }
+}
+
+pub fn parse_properties(s: Span) -> IResult<Span, Vec<Property>> {
+    let document = parse_document(s);
+    let (rest, _) = s.take_split(s.fragment().len());
+    Ok((rest, document.properties))
+}
+
+fn parse_line_break(s: Span) -> IResult<Span, Span> {
+    alt((line_ending, tag("\r")))(s)
+}
+
+/// Take the rest of the logical line, including any folded continuation lines but not the
+/// final line ending.
+fn take_line(s: Span) -> (Span, Span) {
+    let bytes = s.fragment().as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' | b'\n' => {
+                let eol = if bytes[i..].starts_with(b"\r\n") {
+                    2
+                } else {
+                    1
+                };
+                if matches!(bytes.get(i + eol), Some(b' ' | b'\t')) {
+                    i += eol + 1;
+                } else {
+                    break;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    s.take_split(i)
 }
 
 pub fn parse_property(s: Span) -> IResult<Span, Property> {
-    let (s, name_raw) = take_till(|c| c == ';' || c == ':')(s)?;
+    let (s, name_raw) = take_till(|c| matches!(c, ';' | ':' | '\r' | '\n'))(s)?;
     let name = PropertyName::from_str(name_raw.fragment()).ok();
     let mut params = Vec::new();
     let mut sc = s;
@@ -392,12 +812,11 @@ pub fn parse_property(s: Span) -> IResult<Span, Property> {
     let s = sc;
     let (s, colon) = opt(tag(":"))(s)?;
     let (s, value) = if colon.is_some() {
-        let (s, value) = take_till(|c| c == '\r' || c == '\n')(s)?;
+        let (s, value) = take_line(s);
         (s, Some(value))
     } else {
         (s, None)
     };
-    let (s, _) = opt(line_ending)(s)?;
     Ok((
         s,
         Property {
@@ -410,10 +829,17 @@ pub fn parse_property(s: Span) -> IResult<Span, Property> {
 }
 
 fn parse_parameter(s: Span) -> IResult<Span, Parameter> {
-    let (s, param_name) = take_while(|c: char| c.is_alphabetic() || c == '-')(s)?;
+    let (s, param_name) = take_till(|c| matches!(c, '=' | ';' | ':' | '\r' | '\n'))(s)?;
     let (s, equals) = opt(tag("="))(s)?;
     let (s, param_value) = if equals.is_some() {
-        let (s, value) = take_till(|c| c == ';' || c == ':')(s)?;
+        // a list of values, each of which may be quoted
+        let (s, value) = recognize(separated_list1(
+            tag(","),
+            alt((
+                recognize(delimited(tag("\""), take_till(|c| c == '"'), tag("\""))),
+                take_till(|c| matches!(c, ',' | ';' | ':' | '\r' | '\n')),
+            )),
+        ))(s)?;
         (s, Some(value))
     } else {
         (s, None)
@@ -452,7 +878,13 @@ pub fn parse_value(s: Span, typ: ValueType) -> IResult<Span, Value> {
             let (s, time) = parse_time(s)?;
             Ok((s, Value::DateTime(date, time)))
         }
-        ValueType::Duration => Ok((s, Value::Duration(v.to_string()))),
+        ValueType::Duration => match Duration::from_str(v) {
+            Ok(d) => Ok((s, Value::Duration(d))),
+            Err(_) => Err(nom::Err::Failure(nom::error::Error::new(
+                s,
+                nom::error::ErrorKind::Fail,
+            ))),
+        },
         ValueType::Float => match f64::from_str(v) {
             Ok(f) => Ok((s, Value::Float(f))),
             Err(_) => Err(nom::Err::Failure(nom::error::Error::new(
@@ -468,7 +900,13 @@ pub fn parse_value(s: Span, typ: ValueType) -> IResult<Span, Value> {
             ))),
         },
         ValueType::PeriodOfTime => Ok((s, Value::PeriodOfTime(v.to_string()))),
-        ValueType::RecurrenceRule => Ok((s, Value::RecurrenceRule(v.to_string()))),
+        ValueType::RecurrenceRule => match RecurrenceRule::from_str(v) {
+            Ok(r) => Ok((s, Value::RecurrenceRule(Box::new(r)))),
+            Err(_) => Err(nom::Err::Failure(nom::error::Error::new(
+                s,
+                nom::error::ErrorKind::Fail,
+            ))),
+        },
         ValueType::Text => Ok((s, Value::Text(v.to_string()))),
         ValueType::Time => Ok((s, Value::Time(parse_time(s)?.1))),
         ValueType::Uri => Ok((s, Value::Uri(v.to_string()))),
@@ -769,4 +1207,277 @@ mod tests {
         "#]]
         .assert_debug_eq(&parse_property(Span::new("DTEND;incomp")));
     }
+
+    #[test]
+    fn document_recovers_from_errors() {
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR\r\nDTSTART;TZ ID=x:20221008\r\nSUMMARY\r\nDESCRIPTION:folded\r\n  line\r\nEND:VCALENDAR\r\n",
+        ));
+        let properties = document
+            .properties
+            .iter()
+            .map(|p| (*p.name_raw.fragment(), p.value.map(|v| *v.fragment())))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    "BEGIN",
+                    Some(
+                        "VCALENDAR",
+                    ),
+                ),
+                (
+                    "DTSTART",
+                    Some(
+                        "20221008",
+                    ),
+                ),
+                (
+                    "SUMMARY",
+                    None,
+                ),
+                (
+                    "DESCRIPTION",
+                    Some(
+                        "folded\r\n  line",
+                    ),
+                ),
+                (
+                    "END",
+                    Some(
+                        "VCALENDAR",
+                    ),
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&properties);
+        let errors = document
+            .errors
+            .iter()
+            .map(|e| (e.kind.clone(), e.span.location_line(), *e.span.fragment()))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    IllegalNameCharacter(
+                        ' ',
+                    ),
+                    2,
+                    " ",
+                ),
+                (
+                    MissingColon,
+                    3,
+                    "",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&errors);
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let document = parse_document(Span::new(
+            "SUMMARY;LANGUAGE=;incomp\r\n;TZID=x:y\r\nX_FOO;CN=\"a\"b\":x\r\nDESCRIPTION:bell\u{7}\rLOCATION:\u{FFFD}\r\n",
+        ));
+        let errors = document
+            .errors
+            .iter()
+            .map(|e| {
+                (
+                    e.kind.to_string(),
+                    e.span.location_line(),
+                    e.span.get_utf8_column(),
+                    *e.span.fragment(),
+                )
+            })
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    "Parameter value is empty",
+                    1,
+                    18,
+                    "",
+                ),
+                (
+                    "Parameter is missing '=' and a value",
+                    1,
+                    19,
+                    "incomp",
+                ),
+                (
+                    "Missing ':' before the property value",
+                    1,
+                    25,
+                    "",
+                ),
+                (
+                    "Missing property name",
+                    2,
+                    1,
+                    "",
+                ),
+                (
+                    "Illegal character '_' in name, only letters, digits and '-' are allowed",
+                    3,
+                    2,
+                    "_",
+                ),
+                (
+                    "Unexpected characters",
+                    3,
+                    13,
+                    "b\":x",
+                ),
+                (
+                    "Control character '\\u{7}' in value",
+                    4,
+                    17,
+                    "\u{7}",
+                ),
+                (
+                    "Carriage return without a line feed",
+                    4,
+                    18,
+                    "\r",
+                ),
+                (
+                    "Replacement character U+FFFD in value, the file may not be valid UTF-8",
+                    4,
+                    28,
+                    "�",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&errors);
+    }
+
+    #[test]
+    fn components() {
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:a\nBEGIN:VALARM\nEND:VEVENT\nEND:VTODO\nEND:VCALENDAR\n",
+        ));
+        fn describe(component: &Component, depth: usize, out: &mut Vec<String>) {
+            out.push(format!(
+                "{}{} {:?} {:?} {:?}",
+                "  ".repeat(depth),
+                component.name.fragment(),
+                component.begin,
+                component.end,
+                component.properties
+            ));
+            for child in &component.children {
+                describe(child, depth + 1, out);
+            }
+        }
+        let mut out = Vec::new();
+        for component in &document.components {
+            describe(component, 0, &mut out);
+        }
+        expect![[r#"
+            [
+                "VCALENDAR 0 Some(6) []",
+                "  VEVENT 1 Some(4) [2]",
+                "    VALARM 3 None []",
+            ]
+        "#]]
+        .assert_debug_eq(&out);
+        let errors = document
+            .errors
+            .iter()
+            .map(|e| (e.kind.to_string(), e.span.location_line()))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    "Missing END:VALARM for this component",
+                    4,
+                ),
+                (
+                    "END:VTODO without a matching BEGIN",
+                    6,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&errors);
+    }
+
+    #[test]
+    fn uri_values() {
+        let checks = [
+            (ValueType::Uri, "https://example.com/a b"),
+            (ValueType::Uri, "cid:part1.0001@example.org"),
+            (ValueType::Uri, "example.com"),
+            (ValueType::CalAddress, "MAILTO:jsmith@example.com"),
+            (ValueType::CalAddress, "mailto:@"),
+            (ValueType::CalAddress, "mailto:jsmith@"),
+            (
+                ValueType::CalAddress,
+                "urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6",
+            ),
+            (ValueType::CalAddress, "jsmith@example.com"),
+        ]
+        .map(|(t, v)| check_value(t, v));
+        expect![[r#"
+            [
+                Err(
+                    "Invalid URI: invalid path character",
+                ),
+                Ok(
+                    (),
+                ),
+                Err(
+                    "Invalid URI: not URI",
+                ),
+                Ok(
+                    (),
+                ),
+                Err(
+                    "The mailto address is missing the local part before '@'",
+                ),
+                Err(
+                    "The mailto address is missing the domain after '@'",
+                ),
+                Ok(
+                    (),
+                ),
+                Err(
+                    "Invalid URI: not URI",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&checks);
+        let (_, property) = parse_property(Span::new(
+            "ATTENDEE;DIR=\"ldap://example.com:6666/o=ABC%20Industries,c=US???(cn=Jim%20Dolittle)\";SENT-BY=\"mailto:a@example.com\":mailto:c@example.com",
+        ))
+        .unwrap();
+        expect![[r#"
+            [
+                Ok(
+                    (),
+                ),
+                Ok(
+                    (),
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(
+            &property
+                .params
+                .iter()
+                .map(|p| p.check_value_type())
+                .collect::<Vec<_>>(),
+        );
+        let (_, property) = parse_property(Span::new(
+            "ATTENDEE;DELEGATED-TO=\"mailto:a@example.com\",\"mailto:b\":mailto:c@example.com",
+        ))
+        .unwrap();
+        expect![[r#"
+            Err(
+                "The mailto address is missing '@'",
+            )
+        "#]]
+        .assert_debug_eq(&property.params[0].check_value_type());
+    }
 }
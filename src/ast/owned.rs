@@ -0,0 +1,261 @@
+//! An owned version of the ast, detached from the lifetime of the document text so that it can
+//! be cached, sent between threads or serialized.
+
+use serde::{Deserialize, Serialize};
+
+use super::{ParameterName, PropertyName, Span, SyntaxErrorKind};
+
+/// A range of the document, with the text that it covered.
+///
+/// `start` and `end` are byte offsets into the document, `line` and `column` are 1-based like
+/// [`nom_locate::LocatedSpan`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: u32,
+    pub column: usize,
+    pub text: String,
+}
+
+impl From<&Span<'_>> for TextSpan {
+    fn from(span: &Span<'_>) -> Self {
+        Self {
+            start: span.location_offset(),
+            end: span.location_offset() + span.fragment().len(),
+            line: span.location_line(),
+            column: span.get_utf8_column(),
+            text: span.fragment().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name_raw: TextSpan,
+    pub name: Option<ParameterName>,
+    pub value: Option<TextSpan>,
+}
+
+impl From<&super::Parameter<'_>> for Parameter {
+    fn from(parameter: &super::Parameter<'_>) -> Self {
+        Self {
+            name_raw: (&parameter.name_raw).into(),
+            name: parameter.name,
+            value: parameter.value.as_ref().map(TextSpan::from),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Property {
+    pub name_raw: TextSpan,
+    pub name: Option<PropertyName>,
+    pub params: Vec<Parameter>,
+    pub value: Option<TextSpan>,
+}
+
+impl From<&super::Property<'_>> for Property {
+    fn from(property: &super::Property<'_>) -> Self {
+        Self {
+            name_raw: (&property.name_raw).into(),
+            name: property.name,
+            params: property.params.iter().map(Parameter::from).collect(),
+            value: property.value.as_ref().map(TextSpan::from),
+        }
+    }
+}
+
+/// A component, referring to its properties by their index in the [`Document`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Component {
+    pub name: TextSpan,
+    pub begin: usize,
+    pub end: Option<usize>,
+    pub properties: Vec<usize>,
+    pub children: Vec<Component>,
+}
+
+impl From<&super::Component<'_>> for Component {
+    fn from(component: &super::Component<'_>) -> Self {
+        Self {
+            name: (&component.name).into(),
+            begin: component.begin,
+            end: component.end,
+            properties: component.properties.clone(),
+            children: component.children.iter().map(Component::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntaxError {
+    pub span: TextSpan,
+    pub kind: SyntaxErrorKind,
+}
+
+impl From<&super::SyntaxError<'_>> for SyntaxError {
+    fn from(error: &super::SyntaxError<'_>) -> Self {
+        Self {
+            span: (&error.span).into(),
+            kind: error.kind.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Document {
+    pub properties: Vec<Property>,
+    pub components: Vec<Component>,
+    pub errors: Vec<SyntaxError>,
+}
+
+impl From<&super::Document<'_>> for Document {
+    fn from(document: &super::Document<'_>) -> Self {
+        Self {
+            properties: document.properties.iter().map(Property::from).collect(),
+            components: document.components.iter().map(Component::from).collect(),
+            errors: document.errors.iter().map(SyntaxError::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::ast::{parse_document, parse_property};
+
+    #[test]
+    fn owned_property_json() {
+        let (_, property) =
+            parse_property(Span::new("DTSTART;TZID=Europe/London:20221008T170000")).unwrap();
+        let owned = Property::from(&property);
+        expect![[r#"
+            {
+              "name_raw": {
+                "start": 0,
+                "end": 7,
+                "line": 1,
+                "column": 1,
+                "text": "DTSTART"
+              },
+              "name": "DtStart",
+              "params": [
+                {
+                  "name_raw": {
+                    "start": 8,
+                    "end": 12,
+                    "line": 1,
+                    "column": 9,
+                    "text": "TZID"
+                  },
+                  "name": "TZId",
+                  "value": {
+                    "start": 13,
+                    "end": 26,
+                    "line": 1,
+                    "column": 14,
+                    "text": "Europe/London"
+                  }
+                }
+              ],
+              "value": {
+                "start": 27,
+                "end": 42,
+                "line": 1,
+                "column": 28,
+                "text": "20221008T170000"
+              }
+            }"#]]
+        .assert_eq(&serde_json::to_string_pretty(&owned).unwrap());
+    }
+
+    #[test]
+    fn owned_document_round_trip() {
+        let content =
+            "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY;LANGUAGE=en:Party\nDTSTART\nEND:VEVENT\n";
+        let document = parse_document(Span::new(content));
+        let owned = Document::from(&document);
+
+        let json = serde_json::to_string(&owned).unwrap();
+        assert_eq!(serde_json::from_str::<Document>(&json).unwrap(), owned);
+
+        let spans = owned.properties.iter().flat_map(|p| {
+            let params = p
+                .params
+                .iter()
+                .flat_map(|p| [Some(&p.name_raw), p.value.as_ref()]);
+            [Some(&p.name_raw), p.value.as_ref()]
+                .into_iter()
+                .chain(params)
+        });
+        for span in spans.flatten() {
+            assert_eq!(content[span.start..span.end], span.text);
+        }
+        expect![[r#"
+            (
+                [
+                    Component {
+                        name: TextSpan {
+                            start: 6,
+                            end: 15,
+                            line: 1,
+                            column: 7,
+                            text: "VCALENDAR",
+                        },
+                        begin: 0,
+                        end: None,
+                        properties: [],
+                        children: [
+                            Component {
+                                name: TextSpan {
+                                    start: 22,
+                                    end: 28,
+                                    line: 2,
+                                    column: 7,
+                                    text: "VEVENT",
+                                },
+                                begin: 1,
+                                end: Some(
+                                    4,
+                                ),
+                                properties: [
+                                    2,
+                                    3,
+                                ],
+                                children: [],
+                            },
+                        ],
+                    },
+                ],
+                [
+                    SyntaxError {
+                        span: TextSpan {
+                            start: 62,
+                            end: 62,
+                            line: 4,
+                            column: 8,
+                            text: "",
+                        },
+                        kind: MissingColon,
+                    },
+                    SyntaxError {
+                        span: TextSpan {
+                            start: 6,
+                            end: 15,
+                            line: 1,
+                            column: 7,
+                            text: "VCALENDAR",
+                        },
+                        kind: MissingEnd(
+                            "VCALENDAR",
+                        ),
+                    },
+                ],
+            )
+        "#]]
+        .assert_debug_eq(&(&owned.components, &owned.errors));
+    }
+}
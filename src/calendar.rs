@@ -0,0 +1,392 @@
+use std::str::FromStr;
+
+use crate::ast::{check_value, ParameterName, PropertyName};
+use crate::lines::{fold, FOLD_WIDTH};
+use crate::properties::components;
+use crate::value::{escape_text, ValueType};
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum Error {
+    #[error("Unknown property {0:?}")]
+    UnknownProperty(String),
+    #[error("Unknown parameter {parameter:?} on property {property:?}")]
+    UnknownParameter { property: String, parameter: String },
+    #[error("Invalid value for {property:?}: {message}")]
+    InvalidValue { property: String, message: String },
+    #[error("Invalid value for parameter {parameter:?} on property {property:?}: {message}")]
+    InvalidParameterValue {
+        property: String,
+        parameter: String,
+        message: String,
+    },
+    #[error("Property {property:?} is not allowed in a {component}")]
+    PropertyNotAllowed { property: String, component: String },
+}
+
+/// A single owned content line, e.g. `DTSTART;TZID=Europe/London:20221008T170000`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentLine {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    pub value: String,
+}
+
+impl ContentLine {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into().to_uppercase(),
+            params: Vec::new(),
+            value: value.into(),
+        }
+    }
+
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into().to_uppercase(), value.into()));
+        self
+    }
+
+    fn value_type(&self) -> Option<ValueType> {
+        let explicit = self
+            .params
+            .iter()
+            .find(|(n, _)| n == "VALUE")
+            .and_then(|(_, v)| ValueType::from_str(v).ok());
+        explicit.or_else(|| {
+            PropertyName::from_str(&self.name)
+                .ok()
+                .map(|n| n.to_property().value_type())
+        })
+    }
+
+    /// Check the line against the property and parameter registry.
+    pub fn validate(&self) -> Result<(), Error> {
+        let is_extension = |n: &str| n.starts_with("X-");
+        if PropertyName::from_str(&self.name).is_err() && !is_extension(&self.name) {
+            return Err(Error::UnknownProperty(self.name.clone()));
+        }
+        for (param, value) in &self.params {
+            let invalid = |message: String| Error::InvalidParameterValue {
+                property: self.name.clone(),
+                parameter: param.clone(),
+                message,
+            };
+            // a quoted value can't itself contain quotes, and there is no escaping them
+            if value.contains('"') {
+                return Err(invalid("Contains '\"'".to_owned()));
+            }
+            match ParameterName::from_str(param) {
+                Ok(name) => {
+                    check_value(name.to_parameter().value_type(), value).map_err(invalid)?
+                }
+                Err(_) if is_extension(param) => {}
+                Err(_) => {
+                    return Err(Error::UnknownParameter {
+                        property: self.name.clone(),
+                        parameter: param.clone(),
+                    })
+                }
+            }
+        }
+        if let Some(value_type) = self.value_type() {
+            check_value(value_type, &self.value).map_err(|message| Error::InvalidValue {
+                property: self.name.clone(),
+                message,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, out: &mut String) {
+        let mut line = self.name.clone();
+        for (name, value) in &self.params {
+            line.push(';');
+            line.push_str(name);
+            line.push('=');
+            if value.contains([';', ':', ',']) {
+                line.push('"');
+                line.push_str(value);
+                line.push('"');
+            } else {
+                line.push_str(value);
+            }
+        }
+        line.push(':');
+        if matches!(self.value_type(), Some(ValueType::Text)) {
+            line.push_str(&escape_text(&self.value));
+        } else {
+            line.push_str(&self.value);
+        }
+        out.push_str(&fold(&line, FOLD_WIDTH));
+        out.push_str("\r\n");
+    }
+}
+
+/// A `VEVENT` component.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Event {
+    properties: Vec<ContentLine>,
+}
+
+impl Event {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn property(mut self, line: ContentLine) -> Self {
+        self.properties.push(line);
+        self
+    }
+
+    pub fn uid(self, uid: impl Into<String>) -> Self {
+        self.property(ContentLine::new("UID", uid))
+    }
+
+    pub fn dtstamp(self, dtstamp: impl ToString) -> Self {
+        self.property(ContentLine::new("DTSTAMP", dtstamp.to_string()))
+    }
+
+    pub fn summary(self, summary: impl Into<String>) -> Self {
+        self.property(ContentLine::new("SUMMARY", summary))
+    }
+
+    pub fn description(self, description: impl Into<String>) -> Self {
+        self.property(ContentLine::new("DESCRIPTION", description))
+    }
+
+    pub fn location(self, location: impl Into<String>) -> Self {
+        self.property(ContentLine::new("LOCATION", location))
+    }
+
+    pub fn status(self, status: impl Into<String>) -> Self {
+        self.property(ContentLine::new("STATUS", status))
+    }
+
+    pub fn dtstart(self, dtstart: impl ToString) -> Self {
+        self.property(date_or_date_time("DTSTART", dtstart.to_string()))
+    }
+
+    pub fn dtend(self, dtend: impl ToString) -> Self {
+        self.property(date_or_date_time("DTEND", dtend.to_string()))
+    }
+
+    pub fn duration(self, duration: impl Into<String>) -> Self {
+        self.property(ContentLine::new("DURATION", duration))
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        for property in &self.properties {
+            property.validate()?;
+            let Ok(name) = PropertyName::from_str(&property.name) else {
+                continue;
+            };
+            let components = components(name);
+            if !components.is_empty() && !components.contains(&"VEVENT") {
+                return Err(Error::PropertyNotAllowed {
+                    property: property.name.clone(),
+                    component: "VEVENT".to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&self, out: &mut String) {
+        ContentLine::new("BEGIN", "VEVENT").write(out);
+        for property in &self.properties {
+            property.write(out);
+        }
+        ContentLine::new("END", "VEVENT").write(out);
+    }
+}
+
+fn date_or_date_time(name: &str, value: String) -> ContentLine {
+    // a plain date needs the value type overriding, otherwise it is expected to be a DATE-TIME
+    if !value.contains('T') {
+        ContentLine::new(name, value).param("VALUE", "DATE")
+    } else {
+        ContentLine::new(name, value)
+    }
+}
+
+/// A `VCALENDAR` object, built up from its properties and components.
+///
+/// ```
+/// use icalls::calendar::{Calendar, Event};
+///
+/// let ics = Calendar::new()
+///     .event(
+///         Event::new()
+///             .uid("1234@example.com")
+///             .dtstamp("20221008T120000Z")
+///             .summary("Department Party")
+///             .dtstart("20221008T170000Z"),
+///     )
+///     .to_ics()
+///     .unwrap();
+/// assert!(ics.contains("SUMMARY:Department Party\r\n"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calendar {
+    properties: Vec<ContentLine>,
+    events: Vec<Event>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Calendar {
+    pub fn new() -> Self {
+        Self {
+            properties: vec![
+                ContentLine::new("VERSION", "2.0"),
+                ContentLine::new("PRODID", "-//jeffa5//icalls//EN"),
+            ],
+            events: Vec::new(),
+        }
+    }
+
+    pub fn property(mut self, line: ContentLine) -> Self {
+        if let Some(existing) = self.properties.iter_mut().find(|p| p.name == line.name) {
+            *existing = line;
+        } else {
+            self.properties.push(line);
+        }
+        self
+    }
+
+    pub fn prodid(self, prodid: impl Into<String>) -> Self {
+        self.property(ContentLine::new("PRODID", prodid))
+    }
+
+    pub fn method(self, method: impl Into<String>) -> Self {
+        self.property(ContentLine::new("METHOD", method))
+    }
+
+    pub fn event(mut self, event: Event) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        self.properties.iter().try_for_each(ContentLine::validate)?;
+        self.events.iter().try_for_each(Event::validate)
+    }
+
+    /// Validate the calendar and write it out in the iCalendar format.
+    pub fn to_ics(&self) -> Result<String, Error> {
+        self.validate()?;
+        let mut out = String::new();
+        ContentLine::new("BEGIN", "VCALENDAR").write(&mut out);
+        for property in &self.properties {
+            property.write(&mut out);
+        }
+        for event in &self.events {
+            event.write(&mut out);
+        }
+        ContentLine::new("END", "VCALENDAR").write(&mut out);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    #[test]
+    fn build_calendar() {
+        let calendar = Calendar::new().event(
+            Event::new()
+                .uid("19960401T080045Z-4000F192713-0052@example.com")
+                .dtstamp("20221008T120000Z")
+                .summary("Party; with, escapes")
+                .dtstart("20221008")
+                .property(ContentLine::new("X-ACME-COSTCENTER", "42")),
+        );
+        expect![[r#"
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//jeffa5//icalls//EN
+            BEGIN:VEVENT
+            UID:19960401T080045Z-4000F192713-0052@example.com
+            DTSTAMP:20221008T120000Z
+            SUMMARY:Party\; with\, escapes
+            DTSTART;VALUE=DATE:20221008
+            X-ACME-COSTCENTER:42
+            END:VEVENT
+            END:VCALENDAR
+        "#]]
+        .assert_eq(&calendar.to_ics().unwrap().replace("\r\n", "\n"));
+    }
+
+    #[test]
+    fn build_invalid_calendar() {
+        let calendar = Calendar::new().event(Event::new().dtstart("2022-10-08T17:00"));
+        expect![[r#"
+            Err(
+                InvalidValue {
+                    property: "DTSTART",
+                    message: "Length was not 8",
+                },
+            )
+        "#]]
+        .assert_debug_eq(&calendar.to_ics());
+        let calendar = Calendar::new().event(Event::new().property(ContentLine::new("FOO", "")));
+        expect![[r#"
+            Err(
+                UnknownProperty(
+                    "FOO",
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&calendar.to_ics());
+        let calendar = Calendar::new().event(
+            Event::new().property(
+                ContentLine::new("ATTENDEE", "mailto:a@example.com")
+                    .param("DELEGATED-TO", "b@example.com"),
+            ),
+        );
+        expect![[r#"
+            Err(
+                InvalidParameterValue {
+                    property: "ATTENDEE",
+                    parameter: "DELEGATED-TO",
+                    message: "Invalid URI: not URI",
+                },
+            )
+        "#]]
+        .assert_debug_eq(&calendar.to_ics());
+        let calendar = Calendar::new().event(
+            Event::new().property(ContentLine::new("SUMMARY", "Party").param("X-NAME", "\"A\"")),
+        );
+        expect![[r#"
+            Err(
+                InvalidParameterValue {
+                    property: "SUMMARY",
+                    parameter: "X-NAME",
+                    message: "Contains '\"'",
+                },
+            )
+        "#]]
+        .assert_debug_eq(&calendar.to_ics());
+        let calendar = Calendar::new()
+            .event(Event::new().property(ContentLine::new("DUE", "20221008T170000Z")));
+        expect![[r#"
+            Err(
+                PropertyNotAllowed {
+                    property: "DUE",
+                    component: "VEVENT",
+                },
+            )
+        "#]]
+        .assert_debug_eq(&calendar.to_ics());
+    }
+}
@@ -0,0 +1,587 @@
+//! Completion of property and parameter values, worked out from the text before the cursor as
+//! the line being typed is usually incomplete.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, Timelike as _, Utc};
+use lsp_types::{CompletionItem, CompletionItemKind, InsertTextFormat, Position, Range, TextEdit};
+
+use crate::ast::{Document, ParameterName, PropertyName};
+use crate::config::Config;
+use crate::diagnostics::{component_range, timezone_definitions};
+use crate::lines::physical_lines;
+use crate::properties;
+use crate::value::ValueType;
+
+/// Where a value is being typed.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValueContext {
+    Property {
+        name: String,
+        params: Vec<(String, String)>,
+    },
+    Parameter {
+        property: String,
+        parameter: String,
+    },
+}
+
+/// Work out the value being typed at the end of a (unfolded) content line, returning the context
+/// and the part of the value typed so far.
+pub fn value_context(line: &str) -> Option<(ValueContext, &str)> {
+    let mut name_end = None;
+    let mut params = Vec::new();
+    // start of the current parameter name, and of its value once '=' is seen
+    let mut param: Option<(usize, Option<usize>)> = None;
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            ';' | ':' => {
+                name_end.get_or_insert(i);
+                if let Some((start, Some(value))) = param.take() {
+                    params.push((
+                        line[start..value - 1].to_uppercase(),
+                        line[value..i].trim_matches('"').to_owned(),
+                    ));
+                }
+                if c == ':' {
+                    let name = line[..name_end?].to_uppercase();
+                    return Some((ValueContext::Property { name, params }, &line[i + 1..]));
+                }
+                param = Some((i + 1, None));
+            }
+            '=' => {
+                if let Some((_, value @ None)) = &mut param {
+                    *value = Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    let (start, Some(value)) = param? else {
+        return None;
+    };
+    let context = ValueContext::Parameter {
+        property: line[..name_end?].to_uppercase(),
+        parameter: line[start..value - 1].to_uppercase(),
+    };
+    Some((context, line[value..].trim_start_matches('"')))
+}
+
+/// The content line up to `position`, joining any folded lines before it.
+pub fn line_before(content: &str, position: Position) -> String {
+    let lines = physical_lines(content).collect::<Vec<_>>();
+    let Some(current) = lines.get(position.line as usize) else {
+        return String::new();
+    };
+    let mut line = current
+        .content
+        .chars()
+        .take(position.character as usize)
+        .collect::<String>();
+    for previous in lines[..position.line as usize].iter().rev() {
+        match line.strip_prefix([' ', '\t']) {
+            Some(rest) => line = format!("{}{rest}", previous.content),
+            None => break,
+        }
+    }
+    line
+}
+
+/// Properties whose DATE-TIME values must be in UTC.
+const UTC_PROPERTIES: &[PropertyName] = &[
+    PropertyName::Completed,
+    PropertyName::Created,
+    PropertyName::DtStamp,
+    PropertyName::LastModified,
+];
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn format_date_time(date_time: NaiveDateTime, utc: bool) -> String {
+    let z = if utc { "Z" } else { "" };
+    format!("{}{z}", date_time.format("%Y%m%dT%H%M%S"))
+}
+
+/// Relative dates and times, e.g. `now`, formatted for the value type and time zone.
+fn time_values(
+    value_type: ValueType,
+    tzid: Option<&str>,
+    utc_only: bool,
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let zone = tzid
+        .filter(|_| !utc_only)
+        .and_then(|tzid| chrono_tz::Tz::from_str(tzid).ok());
+    // with a TZID the value is local to that zone, otherwise write UTC times. Zones that aren't in
+    // the IANA database can't be converted to, so fall back to the UTC clock.
+    let utc = tzid.is_none() || utc_only;
+    let now = match zone {
+        Some(zone) => now.with_timezone(&zone).naive_local(),
+        None => now.naive_utc(),
+    };
+    let today = now.date();
+    let tomorrow = today + Days::new(1);
+    let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    match value_type {
+        ValueType::DateTime => vec![
+            (
+                "now",
+                format_date_time(now.with_nanosecond(0).unwrap_or(now), utc),
+            ),
+            ("today", format_date_time(today.into(), utc)),
+            ("tomorrow", format_date_time(tomorrow.into(), utc)),
+            (
+                "tomorrow 09:00",
+                format_date_time(tomorrow.and_time(nine), utc),
+            ),
+        ],
+        ValueType::Date => vec![
+            ("today", format_date(today)),
+            ("tomorrow", format_date(tomorrow)),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+fn item(label: &str, insert: String, kind: CompletionItemKind, range: Range) -> CompletionItem {
+    let detail = (label != insert).then(|| insert.clone());
+    CompletionItem {
+        label: label.to_owned(),
+        kind: Some(kind),
+        detail,
+        filter_text: Some(label.to_owned()),
+        text_edit: Some(lsp_types::CompletionTextEdit::Edit(TextEdit {
+            range,
+            new_text: insert,
+        })),
+        ..Default::default()
+    }
+}
+
+/// The name of the innermost component at the given line.
+fn component_at<'a>(document: &'a Document, line: u32) -> Option<&'a str> {
+    document
+        .all_components()
+        .into_iter()
+        .rev()
+        .find(|c| {
+            let range = component_range(document, c);
+            range.start.line <= line && line <= range.end.line
+        })
+        .map(|c| *c.name.fragment())
+}
+
+fn timezone_ids(document: &Document) -> Vec<String> {
+    document
+        .components
+        .iter()
+        .filter(|c| c.is("VCALENDAR"))
+        .flat_map(|c| timezone_definitions(document, c))
+        .filter_map(|(_, p)| Some(p.value?.fragment().to_string()))
+        .collect()
+}
+
+/// A snippet for the rest of a component after `BEGIN:`, with its required properties, calling
+/// `uid` only for components that need one.
+pub fn component_snippet(
+    name: &str,
+    uid: impl FnOnce() -> String,
+    now: DateTime<Utc>,
+    line_ending: &str,
+) -> Option<String> {
+    let now = format_date_time(now.naive_utc().with_nanosecond(0)?, true);
+    let lines = match name {
+        "VEVENT" => vec![
+            format!("UID:{}", uid()),
+            format!("DTSTAMP:{now}"),
+            format!("DTSTART:${{1:{now}}}"),
+            "SUMMARY:${2}".to_owned(),
+        ],
+        "VTODO" | "VJOURNAL" => vec![
+            format!("UID:{}", uid()),
+            format!("DTSTAMP:{now}"),
+            "SUMMARY:${1}".to_owned(),
+        ],
+        "VALARM" => vec![
+            "ACTION:${1|DISPLAY,AUDIO,EMAIL|}".to_owned(),
+            "TRIGGER:${2:-PT15M}".to_owned(),
+            "DESCRIPTION:${3:Reminder}".to_owned(),
+        ],
+        "VTIMEZONE" => vec![
+            "TZID:${1:Europe/London}".to_owned(),
+            "BEGIN:STANDARD".to_owned(),
+            "DTSTART:${2:19701025T020000}".to_owned(),
+            "TZOFFSETFROM:${3:+0100}".to_owned(),
+            "TZOFFSETTO:${4:+0000}".to_owned(),
+            "END:STANDARD".to_owned(),
+        ],
+        _ => return None,
+    };
+    let mut snippet = name.to_owned();
+    for line in lines {
+        snippet.push_str(line_ending);
+        snippet.push_str(&line);
+    }
+    snippet.push_str(line_ending);
+    snippet.push_str("$0");
+    snippet.push_str(line_ending);
+    snippet.push_str("END:");
+    snippet.push_str(name);
+    Some(snippet)
+}
+
+/// Complete the value being typed at `position`, where `typed` is the part of the value on the
+/// current line before the cursor. Components after `BEGIN:` are completed with snippets using
+/// `snippet_line_ending`, or as plain values if it is `None` because the client doesn't support
+/// snippets.
+pub fn value_completions(
+    document: &Document,
+    config: &Config,
+    context: &ValueContext,
+    typed: &str,
+    position: Position,
+    now: DateTime<Utc>,
+    snippet_line_ending: Option<&str>,
+) -> Vec<CompletionItem> {
+    // only replace the last of a list of values
+    let typed = typed.rsplit(',').next().unwrap_or(typed);
+    let range = Range {
+        start: Position {
+            line: position.line,
+            character: position
+                .character
+                .saturating_sub(typed.chars().count() as u32),
+        },
+        end: position,
+    };
+    let mut items = Vec::new();
+    let mut snippets = Vec::new();
+    let mut add = |label: &str, insert: String, kind| {
+        items.push(item(label, insert, kind, range));
+    };
+    match context {
+        ValueContext::Property { name, params } => {
+            let Some(definition) = config.property(name) else {
+                return Vec::new();
+            };
+            let property = PropertyName::from_str(name).ok();
+            let param = |name: &str| {
+                params
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .map(|(_, v)| v.as_str())
+            };
+            let value_type = param("VALUE")
+                .and_then(|v| ValueType::from_str(v).ok())
+                .unwrap_or_else(|| definition.value_type());
+            let utc_only = property.is_some_and(|p| UTC_PROPERTIES.contains(&p));
+            for (label, value) in time_values(value_type, param("TZID"), utc_only, now) {
+                add(label, value, CompletionItemKind::VALUE);
+            }
+            let component = component_at(document, position.line).unwrap_or_default();
+            for value in definition.enumerated_values(component) {
+                let snippet = match snippet_line_ending {
+                    Some(line_ending) if property == Some(PropertyName::Begin) => {
+                        let uid = || uuid::Uuid::new_v4().to_string();
+                        component_snippet(value, uid, now, line_ending)
+                    }
+                    _ => None,
+                };
+                match snippet {
+                    Some(snippet) => snippets.push(CompletionItem {
+                        kind: Some(CompletionItemKind::SNIPPET),
+                        detail: Some(format!("{value} with its required properties")),
+                        insert_text_format: Some(InsertTextFormat::SNIPPET),
+                        ..item(value, snippet, CompletionItemKind::SNIPPET, range)
+                    }),
+                    None => add(value, value.to_string(), CompletionItemKind::ENUM_MEMBER),
+                }
+            }
+            if property == Some(PropertyName::TzId) {
+                for tz in chrono_tz::TZ_VARIANTS {
+                    add(tz.name(), tz.name().to_owned(), CompletionItemKind::VALUE);
+                }
+            }
+        }
+        ValueContext::Parameter {
+            property,
+            parameter,
+        } => {
+            let Some(definition) = config.parameter(parameter) else {
+                return Vec::new();
+            };
+            match ParameterName::from_str(parameter).ok() {
+                Some(ParameterName::TZId) => {
+                    let defined = timezone_ids(document);
+                    for tzid in &defined {
+                        add(tzid, tzid.clone(), CompletionItemKind::REFERENCE);
+                    }
+                    for tz in chrono_tz::TZ_VARIANTS {
+                        if !defined.iter().any(|d| d == tz.name()) {
+                            add(tz.name(), tz.name().to_owned(), CompletionItemKind::VALUE);
+                        }
+                    }
+                }
+                Some(ParameterName::Value) => {
+                    let value_types = match PropertyName::from_str(property) {
+                        Ok(property) => properties::value_types(property),
+                        Err(()) => config
+                            .property(property)
+                            .map(|p| vec![p.value_type()])
+                            .unwrap_or_default(),
+                    };
+                    for value_type in value_types {
+                        let name = value_type.name();
+                        add(name, name.to_owned(), CompletionItemKind::ENUM_MEMBER);
+                    }
+                }
+                _ => {
+                    for value in definition.enumerated_values() {
+                        add(value, value.to_string(), CompletionItemKind::ENUM_MEMBER);
+                    }
+                }
+            }
+        }
+    }
+    items.extend(snippets);
+    let typed = typed.to_lowercase();
+    items.retain(|i| i.label.to_lowercase().contains(&typed));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::ast::{parse_document, Span};
+
+    #[test]
+    fn contexts() {
+        let contexts = [
+            "DTSTART;TZID=Europe/London:2022",
+            "DTSTART;TZID=Eur",
+            "ATTENDEE;ROLE=CHAIR;PARTSTAT=",
+            "DESCRIPTION;ALTREP=\"cid:a;b\":x",
+            "SUMMARY",
+        ]
+        .map(value_context);
+        let line = line_before(
+            "SUMMARY:Department\r\n  Party\r\n",
+            Position {
+                line: 1,
+                character: 4,
+            },
+        );
+        expect!["SUMMARY:Department Pa"].assert_eq(&line);
+        expect![[r#"
+            [
+                Some(
+                    (
+                        Property {
+                            name: "DTSTART",
+                            params: [
+                                (
+                                    "TZID",
+                                    "Europe/London",
+                                ),
+                            ],
+                        },
+                        "2022",
+                    ),
+                ),
+                Some(
+                    (
+                        Parameter {
+                            property: "DTSTART",
+                            parameter: "TZID",
+                        },
+                        "Eur",
+                    ),
+                ),
+                Some(
+                    (
+                        Parameter {
+                            property: "ATTENDEE",
+                            parameter: "PARTSTAT",
+                        },
+                        "",
+                    ),
+                ),
+                Some(
+                    (
+                        Property {
+                            name: "DESCRIPTION",
+                            params: [
+                                (
+                                    "ALTREP",
+                                    "cid:a;b",
+                                ),
+                            ],
+                        },
+                        "x",
+                    ),
+                ),
+                None,
+            ]
+        "#]]
+        .assert_debug_eq(&contexts);
+    }
+
+    #[test]
+    fn completions() {
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR
+BEGIN:VTIMEZONE
+TZID:Custom/Zone
+END:VTIMEZONE
+BEGIN:VTODO
+STATUS:
+DTSTART;TZID=America/New_York:
+DTSTAMP:
+END:VTODO
+END:VCALENDAR
+",
+        ));
+        let now = DateTime::parse_from_rfc3339("2022-10-08T23:30:15Z")
+            .unwrap()
+            .to_utc();
+        let config: Config = toml::from_str(
+            r#"
+[properties.X-ACME-STAGE]
+values = ["DRAFT", "FINAL"]
+
+[parameters.X-ACME-BILLING]
+values = ["HOURLY", "FIXED"]
+"#,
+        )
+        .unwrap();
+        let complete = |line: u32, text: &str| {
+            let (context, typed) = value_context(text).unwrap();
+            let position = Position {
+                line,
+                character: text.chars().count() as u32,
+            };
+            value_completions(
+                &document,
+                &config,
+                &context,
+                typed,
+                position,
+                now,
+                Some("\n"),
+            )
+            .into_iter()
+            .take(5)
+            .map(|i| format!("{} {:?}", i.label, i.detail))
+            .collect::<Vec<_>>()
+        };
+        expect![[r#"
+            [
+                [
+                    "NEEDS-ACTION None",
+                    "COMPLETED None",
+                    "IN-PROCESS None",
+                    "CANCELLED None",
+                ],
+                [
+                    "now Some(\"20221008T193015\")",
+                    "today Some(\"20221008T000000\")",
+                    "tomorrow Some(\"20221009T000000\")",
+                    "tomorrow 09:00 Some(\"20221009T090000\")",
+                ],
+                [
+                    "now Some(\"20221008T233015Z\")",
+                    "today Some(\"20221008T000000Z\")",
+                    "tomorrow Some(\"20221009T000000Z\")",
+                    "tomorrow 09:00 Some(\"20221009T090000Z\")",
+                ],
+                [
+                    "Custom/Zone None",
+                    "Africa/Abidjan None",
+                    "Africa/Accra None",
+                    "Africa/Addis_Ababa None",
+                    "Africa/Algiers None",
+                ],
+                [
+                    "DATE-TIME None",
+                    "DATE None",
+                ],
+                [
+                    "DRAFT None",
+                    "FINAL None",
+                ],
+                [
+                    "FIXED None",
+                ],
+            ]
+        "#]]
+        .assert_debug_eq(&[
+            complete(5, "STATUS:"),
+            complete(6, "DTSTART;TZID=America/New_York:"),
+            complete(7, "DTSTAMP:"),
+            complete(6, "DTSTART;TZID="),
+            complete(6, "DTSTART;VALUE="),
+            complete(6, "X-ACME-STAGE:"),
+            complete(6, "SUMMARY;X-ACME-BILLING=F"),
+        ]);
+        let begin = |snippet_line_ending| {
+            let (context, typed) = value_context("BEGIN:V").unwrap();
+            let position = Position {
+                line: 4,
+                character: 7,
+            };
+            value_completions(
+                &document,
+                &config,
+                &context,
+                typed,
+                position,
+                now,
+                snippet_line_ending,
+            )
+            .into_iter()
+            .map(|i| format!("{} {:?}", i.label, i.insert_text_format))
+            .collect::<Vec<_>>()
+        };
+        expect![[r#"
+            [
+                [
+                    "VCALENDAR None",
+                    "VFREEBUSY None",
+                    "VEVENT Some(Snippet)",
+                    "VTODO Some(Snippet)",
+                    "VJOURNAL Some(Snippet)",
+                    "VTIMEZONE Some(Snippet)",
+                    "VALARM Some(Snippet)",
+                ],
+                [
+                    "VCALENDAR None",
+                    "VEVENT None",
+                    "VTODO None",
+                    "VJOURNAL None",
+                    "VFREEBUSY None",
+                    "VTIMEZONE None",
+                    "VALARM None",
+                ],
+            ]
+        "#]]
+        .assert_debug_eq(&[begin(Some("\n")), begin(None)]);
+        expect![[r#"
+            VEVENT
+            UID:1234@example.com
+            DTSTAMP:20221008T233015Z
+            DTSTART:${1:20221008T233015Z}
+            SUMMARY:${2}
+            $0
+            END:VEVENT"#]]
+        .assert_eq(
+            &component_snippet("VEVENT", || "1234@example.com".to_owned(), now, "\n").unwrap(),
+        );
+    }
+}
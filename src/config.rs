@@ -0,0 +1,435 @@
+//! Project configuration, from an `icalls.toml` file.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{ParameterName, PropertyName};
+use crate::diagnostics::Options;
+use crate::parameters::{self, Parameter};
+use crate::properties::{self, Property};
+use crate::value::ValueType;
+
+pub const FILE_NAME: &str = "icalls.toml";
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(flatten)]
+    pub diagnostics: Options,
+    /// Time zone to also show date-times in when hovering them, e.g. `Europe/London`.
+    pub timezone: Option<String>,
+    /// Names of properties in the order they are preferred in a component.
+    #[serde(default)]
+    pub property_order: Vec<String>,
+    /// Definitions of extension properties, given by name.
+    #[serde(default, with = "named")]
+    pub properties: Vec<PropertyDefinition>,
+    /// Definitions of extension parameters, given by name.
+    #[serde(default, with = "named")]
+    pub parameters: Vec<ParameterDefinition>,
+}
+
+fn text() -> ValueType {
+    ValueType::Text
+}
+
+/// A property defined in the config rather than by RFC 5545.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyDefinition {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default = "text")]
+    pub value_type: ValueType,
+    /// Components the property can be given in, any if empty.
+    #[serde(default)]
+    pub components: Vec<String>,
+    /// The values allowed for the property, any if empty.
+    #[serde(default)]
+    pub values: Vec<String>,
+    #[serde(default)]
+    pub purpose: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+impl Property for PropertyDefinition {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn purpose(&self) -> &str {
+        &self.purpose
+    }
+
+    fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn examples(&self) -> Vec<&str> {
+        self.examples.iter().map(String::as_str).collect()
+    }
+
+    fn keywords(&self) -> Vec<&str> {
+        vec![&self.name]
+    }
+
+    fn components(&self) -> Vec<&str> {
+        self.components.iter().map(String::as_str).collect()
+    }
+
+    fn enumerated_values(&self, _component: &str) -> Vec<&str> {
+        self.values.iter().map(String::as_str).collect()
+    }
+}
+
+/// A parameter defined in the config rather than by RFC 5545.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterDefinition {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default = "text")]
+    pub value_type: ValueType,
+    /// The values allowed for the parameter, any if empty.
+    #[serde(default)]
+    pub values: Vec<String>,
+    #[serde(default)]
+    pub purpose: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+impl Parameter for ParameterDefinition {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn purpose(&self) -> &str {
+        &self.purpose
+    }
+
+    fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn examples(&self) -> Vec<&str> {
+        self.examples.iter().map(String::as_str).collect()
+    }
+
+    fn keywords(&self) -> Vec<&str> {
+        vec![&self.name]
+    }
+
+    fn enumerated_values(&self) -> Vec<&str> {
+        self.values.iter().map(String::as_str).collect()
+    }
+}
+
+/// (De)serialize definitions as a map from their names, e.g. `[properties.X-ACME-COSTCENTER]`.
+mod named {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{ParameterDefinition, PropertyDefinition};
+
+    pub trait Named {
+        fn name(&self) -> &str;
+        fn set_name(&mut self, name: String);
+    }
+
+    impl Named for PropertyDefinition {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn set_name(&mut self, name: String) {
+            self.name = name;
+        }
+    }
+
+    impl Named for ParameterDefinition {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn set_name(&mut self, name: String) {
+            self.name = name;
+        }
+    }
+
+    pub fn serialize<S: Serializer, T: Named + Serialize>(
+        definitions: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(definitions.iter().map(|d| (d.name(), d)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Named + Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        let definitions = BTreeMap::<String, T>::deserialize(deserializer)?;
+        Ok(definitions
+            .into_iter()
+            .map(|(name, mut definition)| {
+                definition.set_name(name);
+                definition
+            })
+            .collect())
+    }
+}
+
+impl Config {
+    /// Override this config with anything that is set in `other`.
+    pub fn merge(&mut self, other: Config) {
+        self.diagnostics.merge(other.diagnostics);
+        self.timezone = other.timezone.or(self.timezone.take());
+        if !other.property_order.is_empty() {
+            self.property_order = other.property_order;
+        }
+        for definition in other.properties {
+            self.properties
+                .retain(|d| !d.name.eq_ignore_ascii_case(&definition.name));
+            self.properties.push(definition);
+        }
+        for definition in other.parameters {
+            self.parameters
+                .retain(|d| !d.name.eq_ignore_ascii_case(&definition.name));
+            self.parameters.push(definition);
+        }
+    }
+
+    pub fn timezone(&self) -> Option<chrono_tz::Tz> {
+        chrono_tz::Tz::from_str(self.timezone.as_deref()?).ok()
+    }
+
+    /// The property with the given name, from RFC 5545 or defined in the config.
+    pub fn property(&self, name: &str) -> Option<&dyn Property> {
+        match PropertyName::from_str(name) {
+            Ok(name) => Some(name.to_property()),
+            Err(()) => self
+                .properties
+                .iter()
+                .find(|d| d.name.eq_ignore_ascii_case(name))
+                .map(|d| d as &dyn Property),
+        }
+    }
+
+    /// All of the properties, from RFC 5545 and defined in the config.
+    pub fn properties(&self) -> Vec<&dyn Property> {
+        let mut all = properties::properties();
+        all.extend(self.properties.iter().map(|d| d as &dyn Property));
+        all
+    }
+
+    /// The parameter with the given name, from RFC 5545 or defined in the config.
+    pub fn parameter(&self, name: &str) -> Option<&dyn Parameter> {
+        match ParameterName::from_str(name) {
+            Ok(name) => Some(name.to_parameter()),
+            Err(()) => self
+                .parameters
+                .iter()
+                .find(|d| d.name.eq_ignore_ascii_case(name))
+                .map(|d| d as &dyn Parameter),
+        }
+    }
+
+    /// All of the parameters, from RFC 5545 and defined in the config.
+    pub fn parameters(&self) -> Vec<&dyn Parameter> {
+        let mut all = parameters::parameters();
+        all.extend(self.parameters.iter().map(|d| d as &dyn Parameter));
+        all
+    }
+
+    /// Position of a property in the preferred order, if it is given one.
+    pub fn property_position(&self, name: &str) -> Option<usize> {
+        self.property_order
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// Settings that are ignored because they don't refer to anything.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = self
+            .diagnostics
+            .unknown_rules()
+            .into_iter()
+            .map(|rule| format!("Unknown rule {rule:?}"))
+            .collect::<Vec<_>>();
+        if let Some(timezone) = &self.timezone {
+            if self.timezone().is_none() {
+                problems.push(format!("Unknown time zone {timezone:?}"));
+            }
+        }
+        for definition in &self.properties {
+            if PropertyName::from_str(&definition.name).is_ok() {
+                problems.push(format!("Property {:?} is already defined", definition.name));
+            }
+        }
+        for definition in &self.parameters {
+            if ParameterName::from_str(&definition.name).is_ok() {
+                problems.push(format!(
+                    "Parameter {:?} is already defined",
+                    definition.name
+                ));
+            }
+        }
+        problems
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Failed to read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Load the `icalls.toml` in `dir`, if there is one.
+pub fn load(dir: &Path) -> Result<Option<Config>, ConfigError> {
+    let path = dir.join(FILE_NAME);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path).map_err(|source| ConfigError::Read {
+        path: path.clone(),
+        source,
+    })?;
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|source| ConfigError::Parse { path, source })
+}
+
+/// Find the config for the files in `dir`, from the closest `icalls.toml` in it or its parents,
+/// falling back to the one in `root`.
+pub fn discover(dir: &Path, root: Option<&Path>) -> Result<Config, ConfigError> {
+    for dir in dir.ancestors() {
+        if let Some(config) = load(dir)? {
+            return Ok(config);
+        }
+    }
+    match root {
+        Some(root) => Ok(load(root)?.unwrap_or_default()),
+        None => Ok(Config::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+fold_width = 100
+timezone = "Europe/London"
+property_order = ["UID", "DTSTAMP", "DTSTART"]
+
+[rules]
+unknown-property = "off"
+ICAL015 = "warning"
+no-such-rule = "off"
+
+[properties.X-ACME-COSTCENTER]
+value_type = "INTEGER"
+components = ["VEVENT"]
+purpose = "The cost center to bill the event to."
+
+[parameters.X-ACME-BILLING]
+values = ["HOURLY", "FIXED"]
+
+[properties.SUMMARY]
+"#,
+        )
+        .unwrap();
+        expect![[r#"
+            Config {
+                diagnostics: Options {
+                    check_line_endings: None,
+                    check_line_length: None,
+                    max_line_length: Some(
+                        100,
+                    ),
+                    rules: {
+                        "ICAL015": Warning,
+                        "no-such-rule": Off,
+                        "unknown-property": Off,
+                    },
+                },
+                timezone: Some(
+                    "Europe/London",
+                ),
+                property_order: [
+                    "UID",
+                    "DTSTAMP",
+                    "DTSTART",
+                ],
+                properties: [
+                    PropertyDefinition {
+                        name: "SUMMARY",
+                        value_type: Text,
+                        components: [],
+                        values: [],
+                        purpose: "",
+                        description: "",
+                        examples: [],
+                    },
+                    PropertyDefinition {
+                        name: "X-ACME-COSTCENTER",
+                        value_type: Integer,
+                        components: [
+                            "VEVENT",
+                        ],
+                        values: [],
+                        purpose: "The cost center to bill the event to.",
+                        description: "",
+                        examples: [],
+                    },
+                ],
+                parameters: [
+                    ParameterDefinition {
+                        name: "X-ACME-BILLING",
+                        value_type: Text,
+                        values: [
+                            "HOURLY",
+                            "FIXED",
+                        ],
+                        purpose: "",
+                        description: "",
+                        examples: [],
+                    },
+                ],
+            }
+        "#]]
+        .assert_debug_eq(&config);
+        expect![[r#"
+            [
+                "Unknown rule \"no-such-rule\"",
+                "Property \"SUMMARY\" is already defined",
+            ]
+        "#]]
+        .assert_debug_eq(&config.problems());
+    }
+}
@@ -0,0 +1,1132 @@
+use std::collections::BTreeMap;
+use std::str::FromStr as _;
+
+use chrono::{FixedOffset, NaiveDateTime, Offset as _, TimeZone as _};
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticTag, Location, Position, Range, TextEdit,
+    Url,
+};
+use nom_locate::LocatedSpan;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{
+    check_value, parse_document, parse_value, Component, Document, ParameterName, Property,
+    PropertyName, Span, SyntaxErrorKind,
+};
+use crate::config::Config;
+use crate::lines::{fold, physical_lines, unfold, FOLD_WIDTH};
+use crate::rules::{Level, Rule};
+use crate::value::{Date, Time, Value, ValueType};
+
+pub fn span_range(span: &Span) -> Range {
+    let start = Position {
+        line: span.location_line() - 1,
+        character: span.get_utf8_column() as u32 - 1,
+    };
+    let fragment = span.fragment();
+    let end = match fragment.rfind('\n') {
+        Some(i) => Position {
+            line: start.line + fragment.matches('\n').count() as u32,
+            character: fragment[i + 1..].chars().count() as u32,
+        },
+        None => Position {
+            line: start.line,
+            character: start.character + fragment.chars().count() as u32,
+        },
+    };
+    Range { start, end }
+}
+
+/// The range of a whole property, from the start of its name to the end of its value.
+pub fn property_range(property: &Property) -> Range {
+    let start = span_range(&property.name_raw).start;
+    let end = match (property.value, property.params.last()) {
+        (Some(value), _) => span_range(&value).end,
+        (None, Some(param)) => span_range(&param.value.unwrap_or(param.name_raw)).end,
+        (None, None) => span_range(&property.name_raw).end,
+    };
+    Range { start, end }
+}
+
+/// The range of a component, from its `BEGIN` to its `END`, or its last property if it has no
+/// `END`.
+pub fn component_range(document: &Document, component: &Component) -> Range {
+    let start = property_range(&document.properties[component.begin]).start;
+    let end = property_range(&document.properties[component.last()]).end;
+    Range { start, end }
+}
+
+fn related(uri: &Url, property: &Property, message: &str) -> DiagnosticRelatedInformation {
+    DiagnosticRelatedInformation {
+        location: Location {
+            uri: uri.clone(),
+            range: property_range(property),
+        },
+        message: message.to_owned(),
+    }
+}
+
+/// How a date or date-time is anchored in time.
+#[derive(Debug, PartialEq, Eq)]
+enum TimeKind<'a> {
+    Date,
+    Floating,
+    Utc,
+    Zoned(&'a str),
+}
+
+impl TimeKind<'_> {
+    fn describe(&self) -> String {
+        match self {
+            TimeKind::Date => "a DATE".to_owned(),
+            TimeKind::Floating => "a floating DATE-TIME".to_owned(),
+            TimeKind::Utc => "a UTC DATE-TIME".to_owned(),
+            TimeKind::Zoned(tz) => format!("a DATE-TIME in {tz}"),
+        }
+    }
+}
+
+fn time_value<'a>(property: &Property<'a>) -> Option<(TimeKind<'a>, Value)> {
+    let value = property.parse_value()?;
+    let kind = match &value {
+        Value::Date(_) => TimeKind::Date,
+        Value::DateTime(_, time) if time.utc => TimeKind::Utc,
+        Value::DateTime(_, _) => match property.param(ParameterName::TZId).and_then(|p| p.value) {
+            Some(tzid) => TimeKind::Zoned(tzid.fragment()),
+            None => TimeKind::Floating,
+        },
+        _ => return None,
+    };
+    Some((kind, value))
+}
+
+/// Check the start, end, due and duration properties of events and to-dos are consistent with
+/// each other.
+pub fn check_times(uri: Option<&Url>, document: &Document) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for component in document.all_components() {
+        if component.is("VEVENT") {
+            check_end(
+                uri,
+                document,
+                component,
+                PropertyName::DtEnd,
+                &mut diagnostics,
+            );
+        } else if component.is("VTODO") {
+            check_end(
+                uri,
+                document,
+                component,
+                PropertyName::Due,
+                &mut diagnostics,
+            );
+        }
+    }
+    diagnostics
+}
+
+fn check_end(
+    uri: Option<&Url>,
+    document: &Document,
+    component: &Component,
+    end_name: PropertyName,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let start = component.property(document, PropertyName::DtStart);
+    let end = component.property(document, end_name);
+    let duration = component.property(document, PropertyName::Duration);
+    let end_label = end_name.to_property().name();
+
+    if let (Some(end), Some(duration)) = (end, duration) {
+        diagnostics.push(Diagnostic {
+            related_information: uri.map(|uri| {
+                vec![
+                    related(uri, end, &format!("{end_label} given here")),
+                    related(uri, duration, "DURATION given here"),
+                ]
+            }),
+            ..Rule::EndWithDuration.diagnostic(
+                span_range(&duration.name_raw),
+                format!(
+                    "{end_label} and DURATION cannot both be given in a {}",
+                    component.name.fragment()
+                ),
+            )
+        });
+    }
+
+    let (Some(start), Some(end)) = (start, end) else {
+        return;
+    };
+    let (Some((start_kind, start_value)), Some((end_kind, end_value))) =
+        (time_value(start), time_value(end))
+    else {
+        return;
+    };
+    let related_information = uri.map(|uri| {
+        vec![
+            related(uri, start, "DTSTART given here"),
+            related(uri, end, &format!("{end_label} given here")),
+        ]
+    });
+    let range = span_range(&end.value.unwrap());
+
+    let message = match (&start_kind, &end_kind) {
+        (TimeKind::Date, TimeKind::Date) => None,
+        (TimeKind::Date, _) | (_, TimeKind::Date) => Some(format!(
+            "{end_label} is {} but DTSTART is {}, they must have the same value type",
+            end_kind.describe(),
+            start_kind.describe()
+        )),
+        (TimeKind::Floating, TimeKind::Floating) => None,
+        (TimeKind::Floating, _) | (_, TimeKind::Floating) => Some(format!(
+            "{end_label} is {} but DTSTART is {}, both must be local times or neither",
+            end_kind.describe(),
+            start_kind.describe()
+        )),
+        _ => None,
+    };
+    if let Some(message) = message {
+        diagnostics.push(Diagnostic {
+            related_information,
+            ..Rule::MismatchedTimeTypes.diagnostic(range, message)
+        });
+        return;
+    }
+
+    // times anchored in different ways are compared as instants, if their time zones are known
+    let in_order = match (&start_value, &end_value) {
+        (Value::Date(s), Value::Date(e)) => Some(s < e),
+        (Value::DateTime(sd, st), Value::DateTime(ed, et)) if start_kind == end_kind => {
+            Some((sd, st) < (ed, et))
+        }
+        _ => match (
+            utc_time(document, &start_kind, &start_value),
+            utc_time(document, &end_kind, &end_value),
+        ) {
+            (Some(start), Some(end)) => Some(start < end),
+            _ => None,
+        },
+    };
+    if in_order == Some(false) {
+        diagnostics.push(Diagnostic {
+            related_information,
+            ..Rule::EndBeforeStart
+                .diagnostic(range, format!("{end_label} must be later than DTSTART"))
+        });
+    }
+}
+
+fn naive_date_time(date: &Date, time: &Time) -> Option<NaiveDateTime> {
+    date.to_naive()?
+        .and_hms_opt(time.hour.into(), time.minute.into(), time.second.into())
+}
+
+/// A UTC or zoned `DATE-TIME` in UTC, if its time zone can be resolved.
+fn utc_time(document: &Document, kind: &TimeKind, value: &Value) -> Option<NaiveDateTime> {
+    let Value::DateTime(date, time) = value else {
+        return None;
+    };
+    let local = naive_date_time(date, time)?;
+    match kind {
+        TimeKind::Utc => Some(local),
+        TimeKind::Zoned(tzid) => Some(local - utc_offset(document, tzid.trim_matches('"'), local)?),
+        _ => None,
+    }
+}
+
+/// The offset from UTC of a local time in a time zone, from the `VTIMEZONE` with the `TZID` in
+/// the document or, if there isn't one, the IANA database.
+fn utc_offset(document: &Document, tzid: &str, local: NaiveDateTime) -> Option<FixedOffset> {
+    let definition = document.all_components().into_iter().find(|c| {
+        c.is("VTIMEZONE")
+            && c.property(document, PropertyName::TzId)
+                .and_then(|p| p.value)
+                .is_some_and(|v| *v.fragment() == tzid)
+    });
+    let Some(definition) = definition else {
+        let tz = chrono_tz::Tz::from_str(tzid).ok()?;
+        return Some(tz.offset_from_local_datetime(&local).earliest()?.fix());
+    };
+    // the offset of the observance that began most recently
+    definition
+        .children
+        .iter()
+        .filter_map(|observance| {
+            let onset = last_onset(document, observance, local)?;
+            let offset = observance
+                .property(document, PropertyName::TzOffsetTo)?
+                .value?;
+            Some((onset, parse_utc_offset(offset.fragment())?))
+        })
+        .max_by_key(|(onset, _)| *onset)
+        .map(|(_, offset)| offset)
+}
+
+/// The last time a `STANDARD` or `DAYLIGHT` observance began before `local`.
+fn last_onset(
+    document: &Document,
+    observance: &Component,
+    local: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    let Some(Value::DateTime(date, time)) = observance
+        .property(document, PropertyName::DtStart)?
+        .parse_value()
+    else {
+        return None;
+    };
+    let start = naive_date_time(&date, &time)?;
+    if start > local {
+        return None;
+    }
+    let mut onset = start;
+    for property in observance.properties(document) {
+        match (property.name, property.value) {
+            (Some(PropertyName::RRule), _) => {
+                let Some(Value::RecurrenceRule(rule)) = property.parse_value() else {
+                    continue;
+                };
+                // rules for time zones are yearly, so the last onset is within a year or so
+                let days = 366 * i64::from(rule.interval);
+                let from = chrono::Duration::try_days(days)
+                    .and_then(|days| local.checked_sub_signed(days))
+                    .unwrap_or(start);
+                let last = rule.occurrences(start, None, from.max(start), local).last();
+                onset = onset.max(last.unwrap_or(start));
+            }
+            (Some(PropertyName::RDate), Some(value)) => {
+                for text in unfold(value.fragment()).split(',') {
+                    if let Ok((_, Value::DateTime(date, time))) =
+                        parse_value(Span::new(text), ValueType::DateTime)
+                    {
+                        let date = naive_date_time(&date, &time).filter(|d| *d <= local);
+                        onset = onset.max(date.unwrap_or(start));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(onset)
+}
+
+/// Parse a `UTC-OFFSET` value like `+0100` or `-053000`.
+fn parse_utc_offset(s: &str) -> Option<FixedOffset> {
+    let sign = match s.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let digits = s.get(1..)?;
+    if !matches!(digits.len(), 4 | 6) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let part = |i: usize| {
+        digits
+            .get(i..i + 2)
+            .map_or(Some(0), |d| d.parse::<i32>().ok())
+    };
+    let seconds = part(0)? * 3600 + part(2)? * 60 + part(4)?;
+    FixedOffset::east_opt(sign * seconds)
+}
+
+/// The `TZID` values of the `VTIMEZONE` components in a calendar.
+pub fn timezone_definitions<'d, 'a>(
+    document: &'d Document<'a>,
+    calendar: &'d Component<'a>,
+) -> Vec<(&'d Component<'a>, &'d Property<'a>)> {
+    calendar
+        .children
+        .iter()
+        .filter(|c| c.is("VTIMEZONE"))
+        .filter_map(|c| Some((c, c.property(document, PropertyName::TzId)?)))
+        .collect()
+}
+
+pub fn tzid_param<'a>(property: &Property<'a>) -> Option<Span<'a>> {
+    property.param(ParameterName::TZId)?.value
+}
+
+/// Check that `TZID` parameters refer to a `VTIMEZONE` in the same calendar, and that every
+/// `VTIMEZONE` is used.
+pub fn check_timezones(uri: Option<&Url>, document: &Document) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for calendar in document.components.iter().filter(|c| c.is("VCALENDAR")) {
+        let definitions = timezone_definitions(document, calendar);
+        let mut used = vec![false; definitions.len()];
+        for property in calendar.descendant_properties(document) {
+            let Some(tzid) = tzid_param(property) else {
+                continue;
+            };
+            let name = tzid.fragment().trim_matches('"');
+            match definitions
+                .iter()
+                .position(|(_, p)| p.value.is_some_and(|v| *v.fragment() == name))
+            {
+                Some(i) => used[i] = true,
+                None => diagnostics.push(Rule::UnknownTimezone.diagnostic(
+                    span_range(&tzid),
+                    format!("No VTIMEZONE with TZID {name:?} in this calendar"),
+                )),
+            }
+            if let Some(value) = property.value {
+                if value.fragment().split(',').any(|v| v.ends_with('Z')) {
+                    diagnostics.push(Diagnostic {
+                        related_information: uri.map(|uri| {
+                            vec![DiagnosticRelatedInformation {
+                                location: Location {
+                                    uri: uri.clone(),
+                                    range: span_range(&tzid),
+                                },
+                                message: "TZID given here".to_owned(),
+                            }]
+                        }),
+                        ..Rule::TzidOnUtcTime.diagnostic(
+                            span_range(&value),
+                            "A UTC time cannot have a TZID parameter".to_owned(),
+                        )
+                    });
+                }
+            }
+        }
+        for ((_, definition), used) in definitions.iter().zip(used) {
+            if used {
+                continue;
+            }
+            let Some(value) = definition.value else {
+                continue;
+            };
+            diagnostics.push(Diagnostic {
+                tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+                ..Rule::UnusedTimezone.diagnostic(
+                    span_range(&value),
+                    format!("VTIMEZONE {:?} is never used", value.fragment()),
+                )
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Options for the checks on the physical lines of a document, and the levels of rules.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Options {
+    pub check_line_endings: Option<bool>,
+    pub check_line_length: Option<bool>,
+    #[serde(alias = "fold_width")]
+    pub max_line_length: Option<usize>,
+    /// Levels of rules, by ID or name.
+    #[serde(default)]
+    pub rules: BTreeMap<String, Level>,
+}
+
+impl Options {
+    /// Override these options with any that are set in `other`.
+    pub fn merge(&mut self, other: Options) {
+        self.check_line_endings = other.check_line_endings.or(self.check_line_endings);
+        self.check_line_length = other.check_line_length.or(self.check_line_length);
+        self.max_line_length = other.max_line_length.or(self.max_line_length);
+        for (key, level) in other.rules {
+            // the same rule may be given by ID in one and name in the other
+            if let Some(rule) = Rule::from_key(&key) {
+                self.rules.retain(|k, _| Rule::from_key(k) != Some(rule));
+            }
+            self.rules.insert(key, level);
+        }
+    }
+
+    /// Keys in `rules` that aren't the ID or name of a rule.
+    pub fn unknown_rules(&self) -> Vec<&str> {
+        self.rules
+            .keys()
+            .filter(|k| Rule::from_key(k).is_none())
+            .map(|k| k.as_str())
+            .collect()
+    }
+
+    fn level(&self, rule: Rule) -> Option<Level> {
+        self.rules
+            .iter()
+            .find(|(k, _)| Rule::from_key(k) == Some(rule))
+            .map(|(_, level)| *level)
+    }
+}
+
+/// A quick fix for a diagnostic, carried in the diagnostic's `data` until a code action is
+/// requested for it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Fix {
+    pub title: String,
+    pub edits: Vec<TextEdit>,
+}
+
+pub fn line_diagnostics(content: &str, options: &Options) -> Vec<Diagnostic> {
+    let lines: Vec<_> = physical_lines(content).collect();
+    let mut diagnostics = Vec::new();
+
+    if options.check_line_endings.unwrap_or(true) {
+        let lf_only: Vec<_> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.ending == "\n")
+            .map(|(i, l)| (i as u32, l.content.chars().count() as u32))
+            .collect();
+        let crlf = lines.iter().any(|l| l.ending == "\r\n");
+        let edit = |&(line, character): &(u32, u32)| TextEdit {
+            range: Range {
+                start: Position { line, character },
+                end: Position { line, character },
+            },
+            new_text: "\r".to_owned(),
+        };
+        let fix = Fix {
+            title: "Convert line endings to CRLF".to_owned(),
+            edits: lf_only.iter().map(edit).collect(),
+        };
+        let data = Some(serde_json::to_value(&fix).unwrap());
+        if crlf {
+            for (i, lf) in lf_only.iter().enumerate() {
+                let &(line, character) = lf;
+                // only the first fixes the whole file, so the fixes don't grow with the square of
+                // the number of lines
+                let data = if i == 0 {
+                    data.clone()
+                } else {
+                    let fix = Fix {
+                        title: "Convert line ending to CRLF".to_owned(),
+                        edits: vec![edit(lf)],
+                    };
+                    Some(serde_json::to_value(&fix).unwrap())
+                };
+                diagnostics.push(Diagnostic {
+                    data,
+                    ..Rule::MixedLineEndings.diagnostic(
+                        Range {
+                            start: Position { line, character },
+                            end: Position {
+                                line: line + 1,
+                                character: 0,
+                            },
+                        },
+                        "Mixed line endings, this line ends with LF rather than CRLF".to_owned(),
+                    )
+                });
+            }
+        } else if let Some(&(line, character)) = lf_only.first() {
+            diagnostics.push(Diagnostic {
+                data,
+                ..Rule::LfLineEndings.diagnostic(
+                    Range {
+                        start: Position { line, character },
+                        end: Position {
+                            line: line + 1,
+                            character: 0,
+                        },
+                    },
+                    "Lines end with LF, CRLF line endings are required".to_owned(),
+                )
+            });
+        }
+    }
+
+    if options.check_line_length.unwrap_or(true) {
+        let max = options.max_line_length.unwrap_or(FOLD_WIDTH);
+        for (i, line) in lines.iter().enumerate() {
+            if line.content.len() <= max {
+                continue;
+            }
+            let line_number = i as u32;
+            let character = line
+                .content
+                .char_indices()
+                .take_while(|(i, c)| i + c.len_utf8() <= max)
+                .count() as u32;
+            let length = line.content.chars().count() as u32;
+            let eol = if line.ending == "\n" { "\n" } else { "\r\n" };
+            let fix = Fix {
+                title: "Fold line".to_owned(),
+                edits: vec![TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: line_number,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: line_number,
+                            character: length,
+                        },
+                    },
+                    new_text: fold(line.content, max).replace("\r\n", eol),
+                }],
+            };
+            diagnostics.push(Diagnostic {
+                data: Some(serde_json::to_value(&fix).unwrap()),
+                ..Rule::LongLine.diagnostic(
+                    Range {
+                        start: Position {
+                            line: line_number,
+                            character,
+                        },
+                        end: Position {
+                            line: line_number,
+                            character: length,
+                        },
+                    },
+                    format!(
+                        "Line is {} octets long, lines should be folded at {} octets",
+                        line.content.len(),
+                        max
+                    ),
+                )
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// Check the properties and parameters defined in the config against their allowed components and
+/// values.
+fn check_extensions(document: &Document, config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let not_one_of = |value: &str, allowed: &[&str]| {
+        let value = value.trim_matches('"');
+        (!allowed.is_empty() && !allowed.iter().any(|a| a.eq_ignore_ascii_case(value)))
+            .then(|| format!("{value:?} is not one of {}", allowed.join(", ")))
+    };
+    // properties outside of any component still have their values and parameters checked
+    let mut components = vec![None; document.properties.len()];
+    for component in document.all_components() {
+        for &i in &component.properties {
+            components[i] = Some(component);
+        }
+    }
+    for (property, component) in document.properties.iter().zip(components) {
+        if property.name.is_none() {
+            if let Some(definition) = config.property(property.name_raw.fragment()) {
+                let components = definition.components();
+                if let Some(component) = component
+                    .filter(|c| !components.is_empty() && !components.iter().any(|n| c.is(n)))
+                {
+                    diagnostics.push(Rule::PropertyNotAllowed.diagnostic(
+                        span_range(&property.name_raw),
+                        format!(
+                            "{} is not allowed in a {}, only in {}",
+                            definition.name(),
+                            component.name.fragment(),
+                            components.join(", ")
+                        ),
+                    ));
+                }
+                if let Some(value) = property.value {
+                    let component = component.map_or("", |c| *c.name.fragment());
+                    let allowed = definition.enumerated_values(component);
+                    let unfolded = unfold(value.fragment());
+                    if let Some(message) = unfolded.split(',').find_map(|v| not_one_of(v, &allowed))
+                    {
+                        diagnostics.push(
+                            Rule::InvalidPropertyValue.diagnostic(span_range(&value), message),
+                        );
+                    }
+                }
+            }
+        }
+        for parameter in &property.params {
+            let (None, Some(value)) = (parameter.name, parameter.value) else {
+                continue;
+            };
+            let Some(definition) = config.parameter(parameter.name_raw.fragment()) else {
+                continue;
+            };
+            let allowed = definition.enumerated_values();
+            let unfolded = unfold(value.fragment());
+            if let Some(message) = unfolded.split(',').find_map(|v| not_one_of(v, &allowed)) {
+                diagnostics
+                    .push(Rule::InvalidParameterValue.diagnostic(span_range(&value), message));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// All of the diagnostics for a document, `uri` being used to relate diagnostics to each other
+/// when there is one.
+pub fn document_diagnostics(uri: Option<&Url>, content: &str, config: &Config) -> Vec<Diagnostic> {
+    let options = &config.diagnostics;
+    let document = parse_document(LocatedSpan::new(content));
+    let mut diagnostics = line_diagnostics(content, options);
+    diagnostics.extend(check_times(uri, &document));
+    diagnostics.extend(check_timezones(uri, &document));
+    diagnostics.extend(check_extensions(&document, config));
+    for error in document.errors {
+        let rule = match error.kind {
+            SyntaxErrorKind::MissingEnd(_) | SyntaxErrorKind::UnmatchedEnd(_) => {
+                Rule::UnbalancedComponent
+            }
+            _ => Rule::SyntaxError,
+        };
+        diagnostics.push(rule.diagnostic(span_range(&error.span), error.kind.to_string()));
+    }
+    for property in document.properties {
+        let definition = config.property(property.name_raw.fragment());
+        if definition.is_none() {
+            diagnostics.push(Rule::UnknownProperty.diagnostic(
+                span_range(&property.name_raw),
+                format!("Unknown property {:?}", property.name_raw.fragment()),
+            ));
+        } else if let (Some(value_type), Some(value)) = (
+            property
+                .value_type()
+                .or(definition.map(|definition| definition.value_type())),
+            property.value,
+        ) {
+            if let Err(e) = check_value(value_type, &unfold(value.fragment())) {
+                diagnostics.push(Rule::InvalidPropertyValue.diagnostic(
+                    span_range(&value),
+                    format!("Failed to match expected type: {:?}\n\n{}", value_type, e),
+                ));
+            }
+        }
+
+        for parameter in property.params {
+            let definition = config.parameter(parameter.name_raw.fragment());
+            // parameters without a value are already reported as syntax errors
+            if definition.is_none() && parameter.value.is_some() {
+                diagnostics.push(Rule::UnknownParameter.diagnostic(
+                    span_range(&parameter.name_raw),
+                    format!("Unknown parameter {:?}", parameter.name_raw.fragment()),
+                ));
+            }
+            if let (Some(definition), Some(value)) = (definition, parameter.value) {
+                if let Err(e) = parameter.check_values(definition.value_type()) {
+                    diagnostics.push(Rule::InvalidParameterValue.diagnostic(
+                        span_range(&value),
+                        format!(
+                            "Failed to match expected type: {:?}\n\n{}",
+                            definition.value_type(),
+                            e
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    diagnostics.retain_mut(|diagnostic| {
+        let Some(level) = Rule::of(diagnostic).and_then(|rule| options.level(rule)) else {
+            return true;
+        };
+        diagnostic.severity = level.severity();
+        diagnostic.severity.is_some()
+    });
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::ast::parse_document;
+
+    fn messages(diagnostics: Vec<Diagnostic>) -> Vec<(u32, String, usize)> {
+        diagnostics
+            .into_iter()
+            .map(|d| {
+                (
+                    d.range.start.line,
+                    d.message,
+                    d.related_information.map_or(0, |r| r.len()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn event_times() {
+        let uri = Url::parse("file:///test.ics").unwrap();
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR
+BEGIN:VEVENT
+DTSTART:20221008T170000Z
+DTEND:20221008T160000Z
+DURATION:PT1H
+END:VEVENT
+BEGIN:VEVENT
+DTSTART;VALUE=DATE:20221008
+DTEND:20221009T160000
+END:VEVENT
+BEGIN:VEVENT
+DTSTART:20221008T170000Z
+DTEND;TZID=Europe/London:20221008T160000
+END:VEVENT
+BEGIN:VEVENT
+DTSTART:20221008T170000Z
+DTEND:20221008T180000
+END:VEVENT
+BEGIN:VTODO
+DTSTART;VALUE=DATE:20221008
+DUE;VALUE=DATE:20221007
+END:VTODO
+BEGIN:VTIMEZONE
+TZID:Custom/Zone
+BEGIN:STANDARD
+DTSTART:19701001T000000
+RRULE:FREQ=YEARLY;BYMONTH=10;BYMONTHDAY=1
+TZOFFSETFROM:+0400
+TZOFFSETTO:+0300
+END:STANDARD
+BEGIN:DAYLIGHT
+DTSTART:19700601T000000
+RRULE:FREQ=YEARLY;BYMONTH=6;BYMONTHDAY=1
+TZOFFSETFROM:+0300
+TZOFFSETTO:+0400
+END:DAYLIGHT
+END:VTIMEZONE
+BEGIN:VEVENT
+DTSTART;TZID=Europe/London:20221008T170000
+DTEND;TZID=America/New_York:20221008T120000
+END:VEVENT
+BEGIN:VEVENT
+DTSTART;TZID=Custom/Zone:20220901T200000
+DTEND:20220901T163000Z
+END:VEVENT
+BEGIN:VEVENT
+DTSTART;TZID=Custom/Zone:20220101T200000
+DTEND:20220101T163000Z
+END:VEVENT
+BEGIN:VEVENT
+DTSTART;TZID=Nowhere:20221008T170000
+DTEND;TZID=Europe/London:20221008T160000
+END:VEVENT
+END:VCALENDAR
+",
+        ));
+        expect![[r#"
+            [
+                (
+                    4,
+                    "DTEND and DURATION cannot both be given in a VEVENT",
+                    2,
+                ),
+                (
+                    3,
+                    "DTEND must be later than DTSTART",
+                    2,
+                ),
+                (
+                    8,
+                    "DTEND is a floating DATE-TIME but DTSTART is a DATE, they must have the same value type",
+                    2,
+                ),
+                (
+                    12,
+                    "DTEND must be later than DTSTART",
+                    2,
+                ),
+                (
+                    16,
+                    "DTEND is a floating DATE-TIME but DTSTART is a UTC DATE-TIME, both must be local times or neither",
+                    2,
+                ),
+                (
+                    20,
+                    "DUE must be later than DTSTART",
+                    2,
+                ),
+                (
+                    39,
+                    "DTEND must be later than DTSTART",
+                    2,
+                ),
+                (
+                    47,
+                    "DTEND must be later than DTSTART",
+                    2,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&messages(check_times(Some(&uri), &document)));
+    }
+
+    #[test]
+    fn timezone_with_huge_interval() {
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR
+BEGIN:VTIMEZONE
+TZID:Huge/Zone
+BEGIN:STANDARD
+DTSTART:19700101T000000
+RRULE:FREQ=YEARLY;INTERVAL=1000000
+TZOFFSETFROM:+0100
+TZOFFSETTO:+0100
+END:STANDARD
+END:VTIMEZONE
+BEGIN:VEVENT
+DTSTART;TZID=Huge/Zone:20221008T170000
+DTEND:20221008T150000Z
+END:VEVENT
+END:VCALENDAR
+",
+        ));
+        expect![[r#"
+            [
+                (
+                    12,
+                    "DTEND must be later than DTSTART",
+                    0,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&messages(check_times(None, &document)));
+    }
+
+    #[test]
+    fn timezone_references() {
+        let uri = Url::parse("file:///test.ics").unwrap();
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR
+BEGIN:VTIMEZONE
+TZID:Europe/London
+END:VTIMEZONE
+BEGIN:VTIMEZONE
+TZID:Europe/Paris
+END:VTIMEZONE
+BEGIN:VEVENT
+DTSTART;TZID=Europe/London:20221008T170000
+DTEND;TZID=America/New_York:20221008T180000Z
+END:VEVENT
+END:VCALENDAR
+",
+        ));
+        expect![[r#"
+            [
+                (
+                    9,
+                    "No VTIMEZONE with TZID \"America/New_York\" in this calendar",
+                    0,
+                ),
+                (
+                    9,
+                    "A UTC time cannot have a TZID parameter",
+                    1,
+                ),
+                (
+                    5,
+                    "VTIMEZONE \"Europe/Paris\" is never used",
+                    0,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&messages(check_timezones(Some(&uri), &document)));
+    }
+
+    #[test]
+    fn line_conformance() {
+        let content = format!(
+            "BEGIN:VCALENDAR\r\nDESCRIPTION:{}\nEND:VCALENDAR\r\n",
+            "a".repeat(70)
+        );
+        let diagnostics = line_diagnostics(&content, &Options::default())
+            .into_iter()
+            .map(|d| (d.range.start.line, d.range.start.character, d.message))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    1,
+                    82,
+                    "Mixed line endings, this line ends with LF rather than CRLF",
+                ),
+                (
+                    1,
+                    75,
+                    "Line is 82 octets long, lines should be folded at 75 octets",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&diagnostics);
+        let fixes = line_diagnostics(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\nEND:VEVENT\nEND:VCALENDAR\n",
+            &Options::default(),
+        )
+        .into_iter()
+        .map(|d| {
+            let fix = serde_json::from_value::<Fix>(d.data.unwrap()).unwrap();
+            (d.range.start.line, fix.title, fix.edits.len())
+        })
+        .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    1,
+                    "Convert line endings to CRLF",
+                    3,
+                ),
+                (
+                    2,
+                    "Convert line ending to CRLF",
+                    1,
+                ),
+                (
+                    3,
+                    "Convert line ending to CRLF",
+                    1,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&fixes);
+    }
+
+    #[test]
+    fn rule_levels() {
+        let content = format!(
+            "BEGIN:VEVENT\r\nFOO:bar\r\nDESCRIPTION:{}\r\nDTSTART:20221008T170000Z\r\nDTEND:20221008T160000Z\r\nEND:VEVENT\r\n",
+            "a".repeat(70)
+        );
+        let mut options = Options::default();
+        options.merge(Options {
+            rules: BTreeMap::from([
+                ("unknown-property".to_owned(), Level::Off),
+                ("ICAL015".to_owned(), Level::Error),
+                ("end-before-start".to_owned(), Level::Warning),
+                ("no-such-rule".to_owned(), Level::Off),
+            ]),
+            ..Default::default()
+        });
+        let config = Config {
+            diagnostics: options.clone(),
+            ..Default::default()
+        };
+        let diagnostics = document_diagnostics(None, &content, &config)
+            .into_iter()
+            .map(|d| {
+                (
+                    d.range.start.line,
+                    d.severity,
+                    d.code,
+                    d.related_information,
+                )
+            })
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    2,
+                    Some(
+                        Error,
+                    ),
+                    Some(
+                        String(
+                            "ICAL015",
+                        ),
+                    ),
+                    None,
+                ),
+                (
+                    4,
+                    Some(
+                        Warning,
+                    ),
+                    Some(
+                        String(
+                            "ICAL009",
+                        ),
+                    ),
+                    None,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&diagnostics);
+        expect![[r#"
+            [
+                "no-such-rule",
+            ]
+        "#]]
+        .assert_debug_eq(&options.unknown_rules());
+    }
+
+    #[test]
+    fn extension_properties() {
+        let content = "BEGIN:VTODO\r\nX-ACME-COSTCENTER:abc\r\nX-ACME-PROJECT:icalls\r\nX-ACME-STAGE:DONE\r\nSUMMARY;X-ACME-BILLING=HOURLY,DAILY:Review\r\nEND:VTODO\r\nX-ACME-STAGE:DONE\r\nSUMMARY;X-ACME-BILLING=DAILY:Loose\r\n";
+        let config: Config = toml::from_str(
+            r#"
+[properties.x-acme-costcenter]
+value_type = "INTEGER"
+components = ["VEVENT"]
+
+[properties.X-ACME-STAGE]
+values = ["DRAFT", "FINAL"]
+
+[parameters.X-ACME-BILLING]
+values = ["HOURLY", "FIXED"]
+"#,
+        )
+        .unwrap();
+        let diagnostics = document_diagnostics(None, content, &config)
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    1,
+                    "x-acme-costcenter is not allowed in a VTODO, only in VEVENT",
+                ),
+                (
+                    3,
+                    "\"DONE\" is not one of DRAFT, FINAL",
+                ),
+                (
+                    4,
+                    "\"DAILY\" is not one of HOURLY, FIXED",
+                ),
+                (
+                    6,
+                    "\"DONE\" is not one of DRAFT, FINAL",
+                ),
+                (
+                    7,
+                    "\"DAILY\" is not one of HOURLY, FIXED",
+                ),
+                (
+                    1,
+                    "Failed to match expected type: Integer\n\ninvalid digit found in string",
+                ),
+                (
+                    2,
+                    "Unknown property \"X-ACME-PROJECT\"",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&diagnostics);
+    }
+
+    #[test]
+    fn folded_value_range() {
+        let content = "BEGIN:VEVENT\r\nDTSTART:2022100é\r\n 8T1700\r\nEND:VEVENT\r\n";
+        let diagnostics = document_diagnostics(None, content, &Config::default())
+            .into_iter()
+            .map(|d| (d.range, d.message))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    Range {
+                        start: Position {
+                            line: 1,
+                            character: 8,
+                        },
+                        end: Position {
+                            line: 2,
+                            character: 7,
+                        },
+                    },
+                    "Failed to match expected type: DateTime\n\nLength was not 8",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&diagnostics);
+    }
+}
@@ -0,0 +1,427 @@
+//! Expanding events and todos into their occurrences, following their recurrence rules, extra
+//! dates, exceptions and overrides.
+
+use std::collections::HashSet;
+use std::str::FromStr as _;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone as _};
+use chrono_tz::Tz;
+
+use crate::ast::{parse_value, Component, Document, Property, PropertyName, Span};
+use crate::diagnostics::tzid_param;
+use crate::lines::unfold;
+use crate::recur::RecurrenceRule;
+use crate::value::{Duration, Value, ValueType};
+
+/// When an occurrence starts or ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum When {
+    Date(NaiveDate),
+    /// A local time, the same in every time zone.
+    Floating(NaiveDateTime),
+    Zoned(DateTime<Tz>),
+}
+
+impl When {
+    /// The point in time, taking dates and local times to be in `tz`.
+    pub fn instant(&self, tz: Tz) -> DateTime<Tz> {
+        match self {
+            When::Date(date) => local(tz, date.and_time(NaiveTime::MIN)),
+            When::Floating(naive) => local(tz, *naive),
+            When::Zoned(time) => time.with_timezone(&tz),
+        }
+    }
+
+    /// The same point in time shown in `tz`, dates and local times are left alone.
+    pub fn in_timezone(self, tz: Tz) -> When {
+        match self {
+            When::Zoned(time) => When::Zoned(time.with_timezone(&tz)),
+            other => other,
+        }
+    }
+
+    fn naive(&self) -> NaiveDateTime {
+        match self {
+            When::Date(date) => date.and_time(NaiveTime::MIN),
+            When::Floating(naive) => *naive,
+            When::Zoned(time) => time.naive_local(),
+        }
+    }
+
+    /// Another time of the same kind, and in the same time zone.
+    fn with_naive(&self, naive: NaiveDateTime) -> When {
+        match self {
+            When::Date(_) => When::Date(naive.date()),
+            When::Floating(_) => When::Floating(naive),
+            When::Zoned(time) => When::Zoned(local(time.timezone(), naive)),
+        }
+    }
+
+    /// The time `duration` later, if it can be represented.
+    fn checked_add(&self, duration: chrono::Duration) -> Option<When> {
+        Some(match self {
+            When::Date(date) => {
+                When::Date(date.checked_add_signed(chrono::Duration::days(duration.num_days()))?)
+            }
+            When::Floating(naive) => When::Floating(naive.checked_add_signed(duration)?),
+            When::Zoned(time) => When::Zoned(time.checked_add_signed(duration)?),
+        })
+    }
+}
+
+impl std::fmt::Display for When {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            When::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            When::Floating(naive) => write!(f, "{}", naive.format("%Y-%m-%dT%H:%M:%S")),
+            When::Zoned(time) => write!(
+                f,
+                "{}",
+                time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            ),
+        }
+    }
+}
+
+/// A local time in a time zone, moved forward if it falls in a gap, like when clocks go forward.
+fn local(tz: Tz, naive: NaiveDateTime) -> DateTime<Tz> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| tz.from_utc_datetime(&naive))
+}
+
+/// Why an occurrence happens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// The `DTSTART` of the component.
+    Start,
+    /// The `DUE` of a to-do without a `DTSTART`.
+    Due,
+    /// An `RRULE` of the component.
+    Rule,
+    /// An `RDATE` of the component.
+    Date,
+    /// A component overriding an occurrence with a `RECURRENCE-ID`.
+    Override,
+}
+
+impl Source {
+    pub fn name(self) -> &'static str {
+        match self {
+            Source::Start => "dtstart",
+            Source::Due => "due",
+            Source::Rule => "rrule",
+            Source::Date => "rdate",
+            Source::Override => "recurrence-id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    /// The name of the component, `VEVENT` or `VTODO`.
+    pub component: String,
+    pub uid: Option<String>,
+    pub summary: Option<String>,
+    pub start: When,
+    pub end: When,
+    pub source: Source,
+}
+
+/// The time zone of a property with a `TZID` parameter, if it is in the IANA database.
+fn timezone(property: &Property) -> Option<Tz> {
+    Tz::from_str(tzid_param(property)?.fragment().trim_matches('"')).ok()
+}
+
+fn parse_when(text: &str, value_type: ValueType, tz: Option<Tz>) -> Option<When> {
+    match parse_value(Span::new(text), value_type).ok()?.1 {
+        Value::Date(date) => Some(When::Date(date.to_naive()?)),
+        Value::DateTime(date, time) => {
+            let naive = date.to_naive()?.and_hms_opt(
+                time.hour.into(),
+                time.minute.into(),
+                time.second.into(),
+            )?;
+            Some(match (time.utc, tz) {
+                (true, _) => When::Zoned(Tz::UTC.from_utc_datetime(&naive)),
+                (false, Some(tz)) => When::Zoned(local(tz, naive)),
+                (false, None) => When::Floating(naive),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The times given in a property, which can have a list of them.
+fn times(property: &Property) -> Vec<When> {
+    let Some(value) = property.value else {
+        return Vec::new();
+    };
+    let value_type = property.value_type().unwrap_or(ValueType::DateTime);
+    let tz = timezone(property);
+    unfold(value.fragment())
+        .split(',')
+        .filter_map(|text| parse_when(text, value_type, tz))
+        .collect()
+}
+
+/// The times given in an `RDATE`, with the ends of any periods.
+fn dates(property: &Property) -> Vec<(When, Option<When>)> {
+    if property.value_type() != Some(ValueType::PeriodOfTime) {
+        return times(property).into_iter().map(|t| (t, None)).collect();
+    }
+    let Some(value) = property.value else {
+        return Vec::new();
+    };
+    let tz = timezone(property);
+    unfold(value.fragment())
+        .split(',')
+        .filter_map(|period| {
+            let (start, end) = period.split_once('/')?;
+            let start = parse_when(start, ValueType::DateTime, tz)?;
+            let end = match end.parse::<Duration>() {
+                Ok(duration) => {
+                    start.checked_add(chrono::Duration::try_seconds(duration.as_seconds())?)?
+                }
+                Err(_) => parse_when(end, ValueType::DateTime, tz)?,
+            };
+            Some((start, Some(end)))
+        })
+        .collect()
+}
+
+fn text(component: &Component, document: &Document, name: PropertyName) -> Option<String> {
+    let value = component.property(document, name)?.value?;
+    Some(unfold(value.fragment()).into_owned())
+}
+
+/// The occurrences of the events and todos in a document that overlap the time from `from` until
+/// `to`, in order. Dates and local times are taken to be in the time zone of `from`, as are times
+/// with a `TZID` that isn't in the IANA database.
+pub fn occurrences(document: &Document, from: DateTime<Tz>, to: DateTime<Tz>) -> Vec<Occurrence> {
+    let tz = from.timezone();
+    let components = document
+        .all_components()
+        .into_iter()
+        .filter(|c| c.is("VEVENT") || c.is("VTODO"))
+        .collect::<Vec<_>>();
+
+    // the occurrences that are replaced by another component, by UID
+    let overridden = components
+        .iter()
+        .filter_map(|component| {
+            let uid = text(component, document, PropertyName::Uid)?;
+            let id = component.property(document, PropertyName::RecurrenceId)?;
+            let id = times(id).into_iter().next()?;
+            Some((uid, id.instant(tz)))
+        })
+        .collect::<HashSet<_>>();
+
+    let mut occurrences = Vec::new();
+    for component in components {
+        let uid = text(component, document, PropertyName::Uid);
+        let end_name = if component.is("VTODO") {
+            PropertyName::Due
+        } else {
+            PropertyName::DtEnd
+        };
+        let (start, source) = match component.property(document, PropertyName::DtStart) {
+            Some(start) => (Some(start), Source::Start),
+            None if component.is("VTODO") => {
+                (component.property(document, PropertyName::Due), Source::Due)
+            }
+            None => (None, Source::Start),
+        };
+        let Some(start) = start.and_then(|p| times(p).into_iter().next()) else {
+            continue;
+        };
+        let end = component
+            .property(document, end_name)
+            .and_then(|p| times(p).into_iter().next());
+        let duration = match (end, start) {
+            (Some(When::Zoned(end)), When::Zoned(start)) => Some(end - start),
+            (Some(end), _) => Some(end.naive() - start.naive()),
+            (None, _) => match component
+                .property(document, PropertyName::Duration)
+                .and_then(Property::parse_value)
+            {
+                Some(Value::Duration(duration)) => {
+                    chrono::Duration::try_seconds(duration.as_seconds())
+                }
+                // a date is for the whole day
+                _ if matches!(start, When::Date(_)) => Some(chrono::Duration::days(1)),
+                _ => Some(chrono::Duration::zero()),
+            },
+        };
+        let start_tz = match start {
+            When::Zoned(time) => time.timezone(),
+            _ => tz,
+        };
+        // occurrences starting before the window can still overlap it, skipping components
+        // lasting too long to work that out
+        let earliest = duration.and_then(|duration| {
+            from.with_timezone(&start_tz)
+                .naive_local()
+                .checked_sub_signed(duration)
+        });
+        let (Some(duration), Some(earliest)) = (duration, earliest) else {
+            continue;
+        };
+        if start.checked_add(duration).is_none() {
+            continue;
+        }
+
+        let mut starts = Vec::new();
+        if component
+            .property(document, PropertyName::RecurrenceId)
+            .is_some()
+        {
+            starts.push((start, None, Source::Override));
+        } else {
+            starts.push((start, None, source));
+            let rules = component
+                .properties(document)
+                .filter(|p| p.name == Some(PropertyName::RRule));
+            for rule in rules {
+                let Some(rule) = rule
+                    .value
+                    .and_then(|v| RecurrenceRule::from_str(&unfold(v.fragment())).ok())
+                else {
+                    continue;
+                };
+                let until = to.with_timezone(&start_tz).naive_local();
+                let tz = matches!(start, When::Zoned(_)).then_some(start_tz);
+                for naive in rule
+                    .occurrences(start.naive(), tz, earliest, until)
+                    .filter(|naive| *naive > start.naive())
+                {
+                    starts.push((start.with_naive(naive), None, Source::Rule));
+                }
+            }
+            for property in component.properties(document) {
+                if property.name == Some(PropertyName::RDate) {
+                    for (start, end) in dates(property) {
+                        starts.push((start, end, Source::Date));
+                    }
+                }
+            }
+
+            let excluded = component
+                .properties(document)
+                .filter(|p| p.name == Some(PropertyName::ExDate))
+                .flat_map(times)
+                .map(|t| t.instant(tz))
+                .collect::<HashSet<_>>();
+            let mut seen = HashSet::new();
+            starts.retain(|(start, _, _)| {
+                let instant = start.instant(tz);
+                let replaced = uid
+                    .as_ref()
+                    .is_some_and(|uid| overridden.contains(&(uid.clone(), instant)));
+                !excluded.contains(&instant) && !replaced && seen.insert(instant)
+            });
+        }
+
+        for (start, end, source) in starts {
+            let Some(end) = end.or_else(|| start.checked_add(duration)) else {
+                continue;
+            };
+            let (start_instant, end_instant) = (start.instant(tz), end.instant(tz));
+            if start_instant >= to || (end_instant <= from && start_instant < from) {
+                continue;
+            }
+            occurrences.push(Occurrence {
+                component: component.name.fragment().to_uppercase(),
+                uid: uid.clone(),
+                summary: text(component, document, PropertyName::Summary),
+                start,
+                end,
+                source,
+            });
+        }
+    }
+    occurrences.sort_by_key(|o| (o.start.instant(tz), o.uid.clone()));
+    occurrences
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use nom_locate::LocatedSpan;
+
+    use super::*;
+    use crate::ast::parse_document;
+
+    #[test]
+    fn expand_document() {
+        let content = "BEGIN:VCALENDAR\r
+BEGIN:VEVENT\r
+UID:standup\r
+SUMMARY:Standup\r
+DTSTART;TZID=Europe/London:20230320T093000\r
+DURATION:PT15M\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,FR;COUNT=6\r
+EXDATE;TZID=Europe/London:20230324T093000\r
+RDATE;VALUE=PERIOD:20230329T120000Z/PT1H\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+SUMMARY:Standup (moved)\r
+RECURRENCE-ID;TZID=Europe/London:20230327T093000\r
+DTSTART;TZID=Europe/London:20230328T100000\r
+DTEND;TZID=Europe/London:20230328T101500\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:report\r
+SUMMARY:Report\r
+DUE;VALUE=DATE:20230331\r
+END:VTODO\r
+BEGIN:VEVENT\r
+UID:holiday\r
+DTSTART;VALUE=DATE:20230101\r
+RRULE:FREQ=YEARLY\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let document = parse_document(LocatedSpan::new(content));
+        let tz = chrono_tz::Europe::London;
+        let from = tz.with_ymd_and_hms(2023, 3, 21, 0, 0, 0).unwrap();
+        let to = tz.with_ymd_and_hms(2023, 4, 1, 0, 0, 0).unwrap();
+        let occurrences = occurrences(&document, from, to)
+            .iter()
+            .map(|o| {
+                format!(
+                    "{} {} {} {:?} {}",
+                    o.start,
+                    o.end,
+                    o.component,
+                    o.summary.as_deref().unwrap_or_default(),
+                    o.source.name()
+                )
+            })
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                "2023-03-28T10:00:00+01:00 2023-03-28T10:15:00+01:00 VEVENT \"Standup (moved)\" recurrence-id",
+                "2023-03-29T12:00:00Z 2023-03-29T13:00:00Z VEVENT \"Standup\" rdate",
+                "2023-03-31 2023-03-31 VTODO \"Report\" due",
+                "2023-03-31T09:30:00+01:00 2023-03-31T09:45:00+01:00 VEVENT \"Standup\" rrule",
+            ]
+        "#]]
+        .assert_debug_eq(&occurrences);
+    }
+
+    #[test]
+    fn huge_duration() {
+        let content = "BEGIN:VEVENT\r\nUID:forever\r\nDTSTART:20230101T000000Z\r\nDURATION:P99999999W\r\nRRULE:FREQ=DAILY\r\nEND:VEVENT\r\n";
+        let document = parse_document(LocatedSpan::new(content));
+        let tz = chrono_tz::UTC;
+        let from = tz.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap();
+        let to = tz.with_ymd_and_hms(2023, 3, 2, 0, 0, 0).unwrap();
+        assert_eq!(occurrences(&document, from, to), Vec::new());
+    }
+}
@@ -0,0 +1,154 @@
+//! Formatting documents in a canonical way, shared by the language server and `icalls fmt`.
+
+use nom_locate::LocatedSpan;
+
+use crate::ast::{parse_document, Component, Document, Property, PropertyName, SyntaxErrorKind};
+use crate::config::Config;
+use crate::lines::{fold, unfold, FOLD_WIDTH};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    #[error("{kind} on line {line}")]
+    Syntax { line: u32, kind: SyntaxErrorKind },
+}
+
+/// Format a document, failing if it has syntax errors as it could then lose content.
+///
+/// Content lines are unfolded and folded again at the configured width, with CRLF line endings
+/// and no blank lines. Property and parameter names, and component names, are uppercased. The
+/// properties of a component come before its subcomponents, those in the configured
+/// `property_order` first.
+pub fn format(content: &str, config: &Config) -> Result<String, FormatError> {
+    let document = parse_document(LocatedSpan::new(content));
+    if let Some(error) = document.errors.first() {
+        return Err(FormatError::Syntax {
+            line: error.span.location_line(),
+            kind: error.kind.clone(),
+        });
+    }
+
+    let options = &config.diagnostics;
+    let width = options
+        .check_line_length
+        .unwrap_or(true)
+        .then(|| options.max_line_length.unwrap_or(FOLD_WIDTH))
+        .filter(|width| *width > 0);
+
+    let mut formatted = String::with_capacity(content.len());
+    for i in order(&document, config) {
+        let line = content_line(&document.properties[i]);
+        match width {
+            Some(width) => formatted.push_str(&fold(&line, width)),
+            None => formatted.push_str(&line),
+        }
+        formatted.push_str("\r\n");
+    }
+    Ok(formatted)
+}
+
+fn content_line(property: &Property) -> String {
+    let mut line = unfold(property.name_raw.fragment()).to_uppercase();
+    for param in &property.params {
+        line.push(';');
+        line.push_str(&unfold(param.name_raw.fragment()).to_uppercase());
+        line.push('=');
+        if let Some(value) = param.value {
+            line.push_str(&unfold(value.fragment()));
+        }
+    }
+    line.push(':');
+    if let Some(value) = property.value {
+        let value = unfold(value.fragment());
+        match property.name {
+            Some(PropertyName::Begin | PropertyName::End) => line.push_str(&value.to_uppercase()),
+            _ => line.push_str(&value),
+        }
+    }
+    line
+}
+
+/// The indices of the properties in the order they should be written out.
+fn order(document: &Document, config: &Config) -> Vec<usize> {
+    let mut order = Vec::with_capacity(document.properties.len());
+    let mut components = document.components.iter().peekable();
+    let mut i = 0;
+    while i < document.properties.len() {
+        match components.next_if(|c| c.begin == i) {
+            Some(component) => {
+                component_order(document, component, config, &mut order);
+                i = component.last() + 1;
+            }
+            None => {
+                order.push(i);
+                i += 1;
+            }
+        }
+    }
+    order
+}
+
+fn component_order(
+    document: &Document,
+    component: &Component,
+    config: &Config,
+    order: &mut Vec<usize>,
+) {
+    order.push(component.begin);
+    let mut properties = component.properties.clone();
+    properties.sort_by_key(|&i| {
+        config
+            .property_position(document.properties[i].name_raw.fragment())
+            .unwrap_or(usize::MAX)
+    });
+    order.extend(properties);
+    for child in &component.children {
+        component_order(document, child, config, order);
+    }
+    order.extend(component.end);
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::diagnostics::Options;
+
+    #[test]
+    fn format_document() {
+        let content = "begin:vcalendar\nBEGIN:VEVENT\nsummary;language=en:A long summary that goes on and on for longer than the fold wi\n dth\n\nBEGIN:VALARM\nACTION:DISPLAY\nEND:VALARM\nDTSTART:20221008T170000Z\nUID:1\nEND:VEVENT\nEND:VCALENDAR\n";
+        let config = Config {
+            diagnostics: Options {
+                max_line_length: Some(42),
+                ..Default::default()
+            },
+            property_order: vec!["UID".to_owned(), "DTSTART".to_owned()],
+            ..Default::default()
+        };
+        let formatted = format(content, &config).unwrap();
+        expect![[r#"
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            UID:1
+            DTSTART:20221008T170000Z
+            SUMMARY;LANGUAGE=en:A long summary that go
+             es on and on for longer than the fold wid
+             th
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            END:VALARM
+            END:VEVENT
+            END:VCALENDAR
+        "#]]
+        .assert_eq(&formatted.replace("\r\n", "\n"));
+        assert_eq!(format(&formatted, &config).unwrap(), formatted);
+
+        expect![[r#"
+            Syntax {
+                line: 2,
+                kind: MissingColon,
+            }
+        "#]]
+        .assert_debug_eq(&format("BEGIN:VEVENT\nSUMMARY\nEND:VEVENT\n", &config).unwrap_err());
+    }
+}
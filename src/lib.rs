@@ -8,3 +8,29 @@ pub mod parameters;
 pub mod ast;
 
 pub mod value;
+
+pub mod recur;
+
+pub mod calendar;
+
+pub mod lines;
+
+pub mod diagnostics;
+
+pub mod references;
+
+pub mod semantic_tokens;
+
+pub mod completion;
+
+pub mod links;
+
+pub mod report;
+
+pub mod rules;
+
+pub mod config;
+
+pub mod format;
+
+pub mod expand;
@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+
+pub const FOLD_WIDTH: usize = 75;
+
+/// Fold a single content line so that no physical line is longer than `width` octets, not
+/// counting the line ending.
+pub fn fold(line: &str, width: usize) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut current = 0;
+    for c in line.chars() {
+        if current + c.len_utf8() > width {
+            folded.push_str("\r\n ");
+            current = 1;
+        }
+        folded.push(c);
+        current += c.len_utf8();
+    }
+    folded
+}
+
+/// A line as it appears in the file, with `ending` being `"\r\n"`, `"\n"` or empty for the last
+/// line.
+#[derive(Debug, PartialEq)]
+pub struct PhysicalLine<'a> {
+    pub content: &'a str,
+    pub ending: &'a str,
+}
+
+pub fn physical_lines(s: &str) -> impl Iterator<Item = PhysicalLine<'_>> {
+    s.split_inclusive('\n').map(|line| {
+        let content = line
+            .strip_suffix('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .unwrap_or(line);
+        PhysicalLine {
+            content,
+            ending: &line[content.len()..],
+        }
+    })
+}
+
+/// The line ending to use for new lines in some text, CRLF unless it only uses LF.
+pub fn line_ending(s: &str) -> &'static str {
+    if s.contains('\n') && !s.contains("\r\n") {
+        "\n"
+    } else {
+        "\r\n"
+    }
+}
+
+/// Remove any line folding from some text, joining continuation lines back together.
+pub fn unfold(s: &str) -> Cow<'_, str> {
+    if s.contains(['\r', '\n']) {
+        Cow::Owned(
+            s.replace("\r\n ", "")
+                .replace("\r\n\t", "")
+                .replace("\n ", "")
+                .replace("\n\t", ""),
+        )
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    #[test]
+    fn fold_long_line() {
+        expect![[r#"
+            "DESCRIPTIO\r\n N:A long \r\n é event"
+        "#]]
+        .assert_debug_eq(&fold("DESCRIPTION:A long é event", 10));
+        expect![[r#"
+            "SUMMARY:short"
+        "#]]
+        .assert_debug_eq(&fold("SUMMARY:short", FOLD_WIDTH));
+    }
+
+    #[test]
+    fn unfold_folded_line() {
+        let line = "DESCRIPTION:A long é event";
+        expect!["DESCRIPTION:A long é event"].assert_eq(&unfold(&fold(line, 10)));
+    }
+}
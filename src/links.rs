@@ -0,0 +1,116 @@
+//! Links to the URIs in a calendar, from URI valued properties and parameters and the addresses of
+//! attendees and organizers.
+
+use lsp_types::Url;
+
+use crate::ast::{Document, ParameterName, PropertyName, Span};
+use crate::lines::unfold;
+use crate::references::unquote;
+use crate::value::ValueType;
+
+#[derive(Debug)]
+pub struct Link<'a> {
+    pub span: Span<'a>,
+    /// The unfolded URI.
+    pub uri: String,
+}
+
+impl Link<'_> {
+    /// Parse the URI, with the reason if it is malformed.
+    pub fn target(&self) -> Result<Url, String> {
+        uriparse::URI::try_from(self.uri.as_str()).map_err(|e| e.to_string())?;
+        Url::parse(&self.uri).map_err(|e| e.to_string())
+    }
+}
+
+pub fn links<'a>(document: &Document<'a>) -> Vec<Link<'a>> {
+    let mut links = Vec::new();
+    let mut push = |span: Span<'a>| {
+        links.push(Link {
+            span,
+            uri: unfold(span.fragment()).into_owned(),
+        })
+    };
+    for property in &document.properties {
+        let linked = match property.name {
+            Some(PropertyName::Url | PropertyName::TzUrl) => true,
+            Some(PropertyName::Attach) => property.value_type() == Some(ValueType::Uri),
+            Some(PropertyName::Attendee | PropertyName::Organizer) => true,
+            _ => false,
+        };
+        if let (true, Some(value)) = (linked, property.value) {
+            push(value);
+        }
+        for param in &property.params {
+            if let (Some(ParameterName::AltRep | ParameterName::Dir), Some(value)) =
+                (param.name, param.value)
+            {
+                push(unquote(value));
+            }
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::ast::parse_document;
+
+    #[test]
+    fn find_links() {
+        let document = parse_document(Span::new(
+            "BEGIN:VEVENT
+URL:https://example.com/event
+ATTACH;FMTTYPE=text/plain;VALUE=BINARY;ENCODING=BASE64:Zm9v
+ATTACH:ftp://example.com/pub/
+ agenda.pdf
+DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\":The meeting
+ORGANIZER;DIR=\"ldap://example.com:6666/o=ABC\":mailto:jsmith@example.com
+TZURL:http://exa mple.com/tz
+END:VEVENT
+",
+        ));
+        let found = links(&document)
+            .iter()
+            .map(|l| (l.span.location_line(), l.uri.clone(), l.target().is_ok()))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    2,
+                    "https://example.com/event",
+                    true,
+                ),
+                (
+                    4,
+                    "ftp://example.com/pub/agenda.pdf",
+                    true,
+                ),
+                (
+                    6,
+                    "cid:part1.0001@example.org",
+                    true,
+                ),
+                (
+                    7,
+                    "mailto:jsmith@example.com",
+                    true,
+                ),
+                (
+                    7,
+                    "ldap://example.com:6666/o=ABC",
+                    true,
+                ),
+                (
+                    8,
+                    "http://exa mple.com/tz",
+                    false,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&found);
+    }
+}
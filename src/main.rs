@@ -1,10 +1,39 @@
+use clap::CommandFactory;
 use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
 use icalls::ast;
-use icalls::ast::parse_properties;
+use icalls::ast::parse_document;
 use icalls::ast::parse_value;
+use icalls::ast::ParameterName;
+use icalls::ast::PropertyName;
 use icalls::ast::SyntaxKind;
+use icalls::completion;
+use icalls::config;
+use icalls::config::Config;
+use icalls::diagnostics;
+use icalls::diagnostics::component_range;
+use icalls::diagnostics::document_diagnostics;
+use icalls::diagnostics::property_range;
+use icalls::diagnostics::span_range;
+use icalls::diagnostics::tzid_param;
+use icalls::diagnostics::Fix;
+use icalls::expand;
+use icalls::format;
+use icalls::lines::line_ending;
+use icalls::lines::unfold;
+use icalls::links::links;
 use icalls::parameters::Parameter;
 use icalls::properties::Property;
+use icalls::references::component_name_ranges;
+use icalls::references::occurrence_at;
+use icalls::references::occurrences;
+use icalls::references::Occurrence;
+use icalls::references::Symbol;
+use icalls::report;
+use icalls::semantic_tokens;
+use icalls::value::convert_timezone;
+use icalls::value::Value;
 use icalls::OpenFiles;
 use lsp_server::ErrorCode;
 use lsp_server::Message;
@@ -23,24 +52,151 @@ use lsp_types::CompletionItemKind;
 use lsp_types::CompletionList;
 use lsp_types::Diagnostic;
 use lsp_types::DiagnosticSeverity;
+use lsp_types::DocumentSymbol;
+use lsp_types::FoldingRange;
+use lsp_types::FoldingRangeKind;
 use lsp_types::InitializeParams;
 use lsp_types::InitializeResult;
 use lsp_types::MarkupContent;
+use lsp_types::MessageType;
 use lsp_types::Position;
 use lsp_types::PositionEncodingKind;
 use lsp_types::PublishDiagnosticsParams;
 use lsp_types::Range;
 use lsp_types::ServerCapabilities;
 use lsp_types::ServerInfo;
+use lsp_types::ShowMessageParams;
+use lsp_types::SymbolKind;
 use lsp_types::TextDocumentSyncKind;
+use lsp_types::TextEdit;
+use lsp_types::Url;
 use nom_locate::LocatedSpan;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr as _;
 
 #[derive(Debug, Clone, Parser)]
 struct Args {
+    /// Run the language server over stdio.
     #[clap(long)]
     stdio: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Check calendar files and print their diagnostics, exiting non-zero if there are errors.
+    Check(CheckArgs),
+    /// Format calendar files in place, or stdin to stdout if no paths are given.
+    Fmt(FmtArgs),
+    /// Print the occurrences of events and todos in a range of time.
+    Expand(ExpandArgs),
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct CheckArgs {
+    /// Files to check, or directories to search for .ics files.
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
+    /// Only report diagnostics at least this severe.
+    #[clap(long, value_enum, default_value_t = Severity::Hint)]
+    min_severity: Severity,
+    /// How to print the diagnostics.
+    #[clap(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+    /// Don't warn about lines ending in LF rather than CRLF.
+    #[clap(long)]
+    allow_lf: bool,
+    /// Maximum length of a line in octets, or 0 to not check line lengths.
+    #[clap(long)]
+    max_line_length: Option<usize>,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct FmtArgs {
+    /// Files to format, or directories to search for .ics files.
+    paths: Vec<PathBuf>,
+    /// Don't write the files, print a diff and exit non-zero if any aren't formatted.
+    #[clap(long)]
+    check: bool,
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct ExpandArgs {
+    /// Files to expand, or directories to search for .ics files.
+    #[clap(required = true)]
+    paths: Vec<PathBuf>,
+    /// Start of the range, a date like 2023-03-01 or a date and time like 2023-03-01T09:00.
+    #[clap(long, value_parser = parse_local_time)]
+    from: chrono::NaiveDateTime,
+    /// End of the range, which isn't included.
+    #[clap(long, value_parser = parse_local_time)]
+    to: chrono::NaiveDateTime,
+    /// Time zone of the range, and to show times in, by default the configured `timezone` or
+    /// UTC. Dates and local times in the calendars are taken to be in it too.
+    #[clap(long)]
+    timezone: Option<chrono_tz::Tz>,
+    /// How to print the occurrences.
+    #[clap(long, value_enum, default_value_t = ExpandFormat::Table)]
+    format: ExpandFormat,
+}
+
+fn parse_local_time(s: &str) -> Result<chrono::NaiveDateTime, String> {
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|f| chrono::NaiveDateTime::parse_from_str(s, f).ok())
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(chrono::NaiveTime::MIN))
+        })
+        .ok_or_else(|| format!("{s:?} is not a date like 2023-03-01 or 2023-03-01T09:00"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExpandFormat {
+    /// Aligned columns.
+    Table,
+    /// A JSON object per occurrence, one per line.
+    Json,
+    /// Comma separated values, with a header.
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Compiler style messages with an excerpt of the source.
+    Human,
+    /// A JSON object per diagnostic, one per line.
+    Json,
+    /// A SARIF 2.1.0 log.
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Severity {
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn of(diagnostic: &Diagnostic) -> Self {
+        match diagnostic.severity {
+            Some(DiagnosticSeverity::ERROR) => Self::Error,
+            Some(DiagnosticSeverity::WARNING) | None => Self::Warning,
+            Some(DiagnosticSeverity::INFORMATION) => Self::Info,
+            _ => Self::Hint,
+        }
+    }
 }
 
 fn log(c: &Connection, message: impl Serialize) {
@@ -61,6 +217,17 @@ fn notify(c: &Connection, method: &str, params: impl Serialize) {
         .unwrap();
 }
 
+fn show_warning(c: &Connection, message: String) {
+    notify(
+        c,
+        ShowMessage::METHOD,
+        ShowMessageParams {
+            typ: MessageType::WARNING,
+            message,
+        },
+    );
+}
+
 fn response_empty(id: RequestId) -> Message {
     Message::Response(Response {
         id,
@@ -80,6 +247,42 @@ fn response_err(id: RequestId, code: i32, message: String) -> Message {
 fn server_capabilities() -> ServerCapabilities {
     ServerCapabilities {
         hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
+        definition_provider: Some(lsp_types::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+        folding_range_provider: Some(lsp_types::FoldingRangeProviderCapability::Simple(true)),
+        inlay_hint_provider: Some(lsp_types::OneOf::Left(true)),
+        document_link_provider: Some(lsp_types::DocumentLinkOptions {
+            resolve_provider: Some(false),
+            work_done_progress_options: Default::default(),
+        }),
+        linked_editing_range_provider: Some(
+            lsp_types::LinkedEditingRangeServerCapabilities::Simple(true),
+        ),
+        semantic_tokens_provider: Some(
+            lsp_types::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                lsp_types::SemanticTokensOptions {
+                    legend: lsp_types::SemanticTokensLegend {
+                        token_types: semantic_tokens::TOKEN_TYPES.to_vec(),
+                        token_modifiers: semantic_tokens::TOKEN_MODIFIERS.to_vec(),
+                    },
+                    range: Some(true),
+                    full: Some(lsp_types::SemanticTokensFullOptions::Bool(true)),
+                    work_done_progress_options: Default::default(),
+                },
+            ),
+        ),
+        references_provider: Some(lsp_types::OneOf::Left(true)),
+        document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
+        rename_provider: Some(lsp_types::OneOf::Right(lsp_types::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        })),
+        code_action_provider: Some(lsp_types::CodeActionProviderCapability::Options(
+            lsp_types::CodeActionOptions {
+                code_action_kinds: Some(vec![lsp_types::CodeActionKind::QUICKFIX]),
+                ..Default::default()
+            },
+        )),
         completion_provider: Some(lsp_types::CompletionOptions {
             resolve_provider: Some(true),
             ..Default::default()
@@ -88,6 +291,7 @@ fn server_capabilities() -> ServerCapabilities {
             lsp_types::TextDocumentSyncOptions {
                 open_close: Some(true),
                 change: Some(TextDocumentSyncKind::INCREMENTAL),
+                save: Some(lsp_types::TextDocumentSyncSaveOptions::Supported(true)),
                 ..Default::default()
             },
         )),
@@ -95,12 +299,17 @@ fn server_capabilities() -> ServerCapabilities {
     }
 }
 
-fn connect(stdio: bool) -> (lsp_types::InitializeParams, Connection, IoThreads) {
-    let (connection, io) = if stdio {
-        Connection::stdio()
-    } else {
-        panic!("No connection mode given, e.g. --stdio");
-    };
+/// The id of the request registering for changes to config files.
+const WATCH_CONFIGS: &str = "watch-configs";
+
+fn is_config(uri: &Url) -> bool {
+    uri.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .is_some_and(|name| name == config::FILE_NAME)
+}
+
+fn connect() -> (lsp_types::InitializeParams, Connection, IoThreads) {
+    let (connection, io) = Connection::stdio();
     let (id, params) = connection.initialize_start().unwrap();
     let mut caps = server_capabilities();
     let init_params = serde_json::from_value::<InitializeParams>(params).unwrap();
@@ -115,25 +324,20 @@ fn connect(stdio: bool) -> (lsp_types::InitializeParams, Connection, IoThreads)
             .unwrap_or(PositionEncodingKind::UTF16);
         caps.position_encoding = Some(pe);
     }
-    let init_opts = if let Some(io) = &init_params.initialization_options {
-        match serde_json::from_value::<InitializationOptions>(io.clone()) {
-            Ok(v) => v,
-            Err(err) => {
-                notify(
-                    &connection,
-                    ShowMessage::METHOD,
-                    format!("Invalid initialization options: {err}"),
-                );
-                panic!("Invalid initialization options: {err}")
-            }
+    let init_opts = match init_params
+        .initialization_options
+        .clone()
+        .map(serde_json::from_value::<InitializationOptions>)
+    {
+        Some(Ok(options)) => options,
+        Some(Err(err)) => {
+            show_warning(
+                &connection,
+                format!("Invalid initialization options: {err}"),
+            );
+            InitializationOptions::default()
         }
-    } else {
-        notify(
-            &connection,
-            ShowMessage::METHOD,
-            "No initialization options given, need it for vcard directory location at least",
-        );
-        panic!("No initialization options given, need it for vcard directory location at least")
+        None => InitializationOptions::default(),
     };
     if !init_opts.enable_completion.unwrap_or(true) {
         caps.completion_provider = None;
@@ -141,6 +345,9 @@ fn connect(stdio: bool) -> (lsp_types::InitializeParams, Connection, IoThreads)
     if !init_opts.enable_hover.unwrap_or(true) {
         caps.hover_provider = None;
     }
+    if !init_opts.enable_inlay_hints.unwrap_or(true) {
+        caps.inlay_hint_provider = None;
+    }
     let init_result = InitializeResult {
         capabilities: caps,
         server_info: Some(ServerInfo {
@@ -157,23 +364,145 @@ fn connect(stdio: bool) -> (lsp_types::InitializeParams, Connection, IoThreads)
 
 struct Server {
     open_files: OpenFiles,
+    options: InitializationOptions,
+    /// Where to find the config file for files outside of any directory with one.
+    root: Option<PathBuf>,
+    /// The config files found for each directory, before the client's options are merged in.
+    configs: HashMap<Option<PathBuf>, Config>,
+    /// Problems with config files that have already been shown.
+    config_problems: HashSet<String>,
+    /// Whether the client can insert completions as snippets.
+    snippet_support: bool,
+    /// Notifications to send after handling the current message.
+    notifications: Vec<Message>,
     shutdown: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 struct InitializationOptions {
     enable_completion: Option<bool>,
     enable_hover: Option<bool>,
+    enable_inlay_hints: Option<bool>,
+    #[serde(flatten)]
+    config: Config,
 }
 
 impl Server {
-    fn new(_c: &Connection, _params: lsp_types::InitializeParams) -> Self {
+    fn new(c: &Connection, params: lsp_types::InitializeParams) -> Self {
+        #[allow(deprecated)]
+        let root = params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| &folder.uri)
+            .or(params.root_uri.as_ref())
+            .and_then(|uri| uri.to_file_path().ok());
+        let options: InitializationOptions = params
+            .initialization_options
+            .and_then(|io| serde_json::from_value(io).ok())
+            .unwrap_or_default();
+        for problem in options.config.problems() {
+            show_warning(c, problem);
+        }
+        let snippet_support = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|t| t.completion.as_ref())
+            .and_then(|c| c.completion_item.as_ref())
+            .and_then(|c| c.snippet_support)
+            .unwrap_or(false);
+        let watch_files = params
+            .capabilities
+            .workspace
+            .and_then(|w| w.did_change_watched_files)
+            .and_then(|w| w.dynamic_registration)
+            .unwrap_or(false);
+        if watch_files {
+            // reload configs when they change, even when they aren't open in the editor
+            let options = lsp_types::DidChangeWatchedFilesRegistrationOptions {
+                watchers: vec![lsp_types::FileSystemWatcher {
+                    glob_pattern: lsp_types::GlobPattern::String(format!(
+                        "**/{}",
+                        config::FILE_NAME
+                    )),
+                    kind: None,
+                }],
+            };
+            let params = lsp_types::RegistrationParams {
+                registrations: vec![lsp_types::Registration {
+                    id: WATCH_CONFIGS.to_owned(),
+                    method: lsp_types::notification::DidChangeWatchedFiles::METHOD.to_owned(),
+                    register_options: serde_json::to_value(options).ok(),
+                }],
+            };
+            c.sender
+                .send(Message::Request(Request::new(
+                    RequestId::from(WATCH_CONFIGS.to_owned()),
+                    lsp_types::request::RegisterCapability::METHOD.to_owned(),
+                    params,
+                )))
+                .unwrap();
+        }
         Self {
             open_files: OpenFiles::default(),
+            options,
+            root,
+            configs: HashMap::new(),
+            config_problems: HashSet::new(),
+            snippet_support,
+            notifications: Vec::new(),
             shutdown: false,
         }
     }
 
+    /// The config for a file, from the closest `icalls.toml` and overridden by the client's
+    /// options.
+    fn config(&mut self, file: &str) -> Config {
+        let dir = Url::parse(file)
+            .ok()
+            .and_then(|uri| uri.to_file_path().ok())
+            .and_then(|path| path.parent().map(Path::to_owned));
+        let mut config = match self.configs.get(&dir) {
+            Some(config) => config.clone(),
+            None => {
+                let config = self.load_config(dir.as_deref());
+                self.configs.insert(dir, config.clone());
+                config
+            }
+        };
+        config.merge(self.options.config.clone());
+        config
+    }
+
+    /// Find and load the config for a directory, showing any problems with it.
+    fn load_config(&mut self, dir: Option<&Path>) -> Config {
+        let discovered = match (dir, &self.root) {
+            (Some(dir), root) => config::discover(dir, root.as_deref()),
+            (None, Some(root)) => config::load(root).map(Option::unwrap_or_default),
+            (None, None) => Ok(Config::default()),
+        };
+        let (config, mut problems) = match discovered {
+            Ok(config) => {
+                let problems = config.problems();
+                (config, problems)
+            }
+            Err(err) => (Config::default(), vec![err.to_string()]),
+        };
+        problems.retain(|p| self.config_problems.insert(p.clone()));
+        for problem in problems {
+            self.notifications
+                .push(Message::Notification(Notification::new(
+                    ShowMessage::METHOD.to_owned(),
+                    ShowMessageParams {
+                        typ: MessageType::WARNING,
+                        message: problem,
+                    },
+                )));
+        }
+        config
+    }
+
     fn serve(mut self, c: Connection) -> Result<(), String> {
         loop {
             match c.receiver.recv().unwrap() {
@@ -196,6 +525,39 @@ impl Server {
                         lsp_types::request::ResolveCompletionItem::METHOD => {
                             self.handle_resolve_completion_item_request(r)
                         }
+                        lsp_types::request::GotoDefinition::METHOD => {
+                            self.handle_goto_definition_request(r)
+                        }
+                        lsp_types::request::References::METHOD => self.handle_references_request(r),
+                        lsp_types::request::PrepareRenameRequest::METHOD => {
+                            self.handle_prepare_rename_request(r)
+                        }
+                        lsp_types::request::Rename::METHOD => self.handle_rename_request(r),
+                        lsp_types::request::DocumentSymbolRequest::METHOD => {
+                            self.handle_document_symbol_request(r)
+                        }
+                        lsp_types::request::FoldingRangeRequest::METHOD => {
+                            self.handle_folding_range_request(r)
+                        }
+                        lsp_types::request::SemanticTokensFullRequest::METHOD => {
+                            self.handle_semantic_tokens_full_request(r)
+                        }
+                        lsp_types::request::SemanticTokensRangeRequest::METHOD => {
+                            self.handle_semantic_tokens_range_request(r)
+                        }
+                        lsp_types::request::DocumentLinkRequest::METHOD => {
+                            self.handle_document_link_request(r)
+                        }
+                        lsp_types::request::LinkedEditingRange::METHOD => {
+                            self.handle_linked_editing_range_request(r)
+                        }
+                        lsp_types::request::InlayHintRequest::METHOD => {
+                            self.handle_inlay_hint_request(r)
+                        }
+                        lsp_types::request::CodeActionRequest::METHOD => {
+                            self.handle_code_action_request(r)
+                        }
+                        lsp_types::request::Formatting::METHOD => self.handle_formatting_request(r),
                         lsp_types::request::Shutdown::METHOD => {
                             self.shutdown = true;
                             vec![response_empty(r.id)]
@@ -205,10 +567,11 @@ impl Server {
                             vec![]
                         }
                     };
-                    for message in messages {
+                    for message in messages.into_iter().chain(self.notifications.drain(..)) {
                         c.sender.send(message).unwrap();
                     }
                 }
+                Message::Response(r) if r.id == RequestId::from(WATCH_CONFIGS.to_owned()) => {}
                 Message::Response(r) => log(&c, format!("Unmatched response received: {}", r.id)),
                 Message::Notification(n) => {
                     let messages = match &n.method[..] {
@@ -221,6 +584,15 @@ impl Server {
                         lsp_types::notification::DidCloseTextDocument::METHOD => {
                             self.handle_did_close_text_document_notification(n)
                         }
+                        lsp_types::notification::DidChangeConfiguration::METHOD => {
+                            self.handle_did_change_configuration_notification(&c, n)
+                        }
+                        lsp_types::notification::DidChangeWatchedFiles::METHOD => {
+                            self.handle_did_change_watched_files_notification(n)
+                        }
+                        lsp_types::notification::DidSaveTextDocument::METHOD => {
+                            self.handle_did_save_text_document_notification(n)
+                        }
                         lsp_types::notification::Exit::METHOD => {
                             if self.shutdown {
                                 return Ok(());
@@ -235,7 +607,7 @@ impl Server {
                             Vec::new()
                         }
                     };
-                    for message in messages {
+                    for message in messages.into_iter().chain(self.notifications.drain(..)) {
                         c.sender.send(message).unwrap()
                     }
                 }
@@ -247,12 +619,11 @@ impl Server {
         let tdp = serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)
             .unwrap();
 
+        let config = self.config(tdp.text_document.uri.as_ref());
         let content = self.open_files.get(tdp.text_document.uri.as_ref());
-        let Ok((_, ast)) = ast::parse_properties(LocatedSpan::new(content)) else {
-            return vec![response_empty(request.id)];
-        };
+        let document = ast::parse_document(LocatedSpan::new(content));
 
-        'outer: for property in ast {
+        'outer: for property in document.properties {
             if property.name_raw.location_line() - 1 < tdp.position.line {
                 continue;
             }
@@ -263,8 +634,8 @@ impl Server {
             let ns = property.name_raw.get_utf8_column() - 1;
             let nl = property.name_raw.fragment().len();
             if (ns..(ns + nl)).contains(&(tdp.position.character as usize)) {
-                if let Some(name) = property.name {
-                    let text = render_property(name.to_property());
+                if let Some(definition) = config.property(property.name_raw.fragment()) {
+                    let text = render_property(definition);
                     let resp = lsp_types::Hover {
                         contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
                             kind: lsp_types::MarkupKind::Markdown,
@@ -278,12 +649,12 @@ impl Server {
                 }
             }
 
-            for param in property.params {
+            for param in &property.params {
                 let ps = param.name_raw.get_utf8_column() - 1;
                 let pl = param.name_raw.fragment().len();
                 if (ps..(ps + pl)).contains(&(tdp.position.character as usize)) {
-                    if let Some(name) = param.name {
-                        let text = render_parameter(name.to_parameter());
+                    if let Some(definition) = config.parameter(param.name_raw.fragment()) {
+                        let text = render_parameter(definition);
                         let resp = lsp_types::Hover {
                             contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
                                 kind: lsp_types::MarkupKind::Markdown,
@@ -302,16 +673,32 @@ impl Server {
                 let ns = value.get_utf8_column() - 1;
                 let nl = value.fragment().len();
                 if (ns..(ns + nl)).contains(&(tdp.position.character as usize)) {
-                    if let Some(name) = property.name {
-                        match parse_value(value, name.to_property().value_type()) {
+                    if let Some(definition) = config.property(property.name_raw.fragment()) {
+                        match parse_value(value, definition.value_type()) {
                             Ok((_, v)) => {
+                                let mut text = v.prettify();
+                                if let (Value::DateTime(date, time), Some(tz)) =
+                                    (&v, config.timezone())
+                                {
+                                    let tzid = tzid_param(&property)
+                                        .map(|tzid| tzid.fragment().trim_matches('"'));
+                                    if let Some((date, time)) =
+                                        convert_timezone(date, time, tzid, tz)
+                                    {
+                                        let converted = Value::DateTime(date, time);
+                                        text.push_str(&format!(
+                                            "\n\n{} in {tz}",
+                                            converted.prettify()
+                                        ));
+                                    }
+                                }
                                 return vec![response_ok(
                                     request.id,
                                     lsp_types::Hover {
                                         contents: lsp_types::HoverContents::Markup(
                                             lsp_types::MarkupContent {
                                                 kind: lsp_types::MarkupKind::Markdown,
-                                                value: v.prettify(),
+                                                value: text,
                                             },
                                         ),
                                         range: None,
@@ -349,14 +736,33 @@ impl Server {
 
         let limit = 100;
 
-        tdp.position.character = tdp.position.character.saturating_sub(1);
-
+        let config = self.config(tdp.text_document.uri.as_ref());
         let content = self.open_files.get(tdp.text_document.uri.as_ref());
-        let Ok((_, ast)) = ast::parse_properties(LocatedSpan::new(content)) else {
-            return vec![response_empty(request.id)];
-        };
+        let document = ast::parse_document(LocatedSpan::new(content));
+
+        let line = completion::line_before(content, tdp.position);
+        if let Some((context, typed)) = completion::value_context(&line) {
+            let mut completion_items = completion::value_completions(
+                &document,
+                &config,
+                &context,
+                typed,
+                tdp.position,
+                chrono::Utc::now(),
+                self.snippet_support.then(|| line_ending(content)),
+            );
+            let is_incomplete = completion_items.len() > limit;
+            completion_items.truncate(limit);
+            let resp = lsp_types::CompletionResponse::List(CompletionList {
+                is_incomplete,
+                items: completion_items,
+            });
+            return vec![response_ok(request.id, resp)];
+        }
 
-        for property in ast {
+        tdp.position.character = tdp.position.character.saturating_sub(1);
+
+        for property in document.properties {
             if property.name_raw.location_line() - 1 < tdp.position.line {
                 continue;
             }
@@ -374,14 +780,23 @@ impl Server {
                     .take(tdp.position.character as usize - ns)
                     .collect::<String>()
                     .to_lowercase();
-                let completion_items: Vec<_> = icalls::properties::properties()
+                let completion_items: Vec<_> = config
+                    .properties()
                     .into_iter()
-                    .filter(|p| p.keywords().iter().any(|kw| kw.contains(&lower_word)))
+                    .filter(|p| matches_keyword(p.keywords(), &lower_word))
                     .map(|p| CompletionItem {
                         label: p.name().to_owned(),
                         kind: Some(CompletionItemKind::TEXT),
-                        data: Some(serde_json::to_value(SyntaxKind::Property).unwrap()),
-                        ..Default::default()
+                        // preferred properties first, in order
+                        sort_text: Some(match config.property_position(p.name()) {
+                            Some(i) => format!("0{i:04}"),
+                            None => format!("1{}", p.name()),
+                        }),
+                        ..completion_documentation(
+                            PropertyName::from_str(p.name()).is_err(),
+                            SyntaxKind::Property,
+                            || render_property(p),
+                        )
                     })
                     .collect();
                 let resp = lsp_types::CompletionResponse::List(CompletionList {
@@ -402,14 +817,18 @@ impl Server {
                         .take(tdp.position.character as usize - ns)
                         .collect::<String>()
                         .to_lowercase();
-                    let completion_items: Vec<_> = icalls::parameters::parameters()
+                    let completion_items: Vec<_> = config
+                        .parameters()
                         .into_iter()
-                        .filter(|p| p.keywords().iter().any(|kw| kw.contains(&lower_word)))
+                        .filter(|p| matches_keyword(p.keywords(), &lower_word))
                         .map(|p| CompletionItem {
                             label: p.name().to_owned(),
                             kind: Some(CompletionItemKind::TEXT),
-                            data: Some(serde_json::to_value(SyntaxKind::Parameter).unwrap()),
-                            ..Default::default()
+                            ..completion_documentation(
+                                ParameterName::from_str(p.name()).is_err(),
+                                SyntaxKind::Parameter,
+                                || render_parameter(p),
+                            )
                         })
                         .collect();
                     let resp = lsp_types::CompletionResponse::List(CompletionList {
@@ -443,7 +862,8 @@ impl Server {
                     .map(render_parameter)
                     .unwrap_or_default(),
             },
-            None => String::new(),
+            // values don't have any documentation, and config definitions come with theirs
+            None => return vec![response_ok(request.id, ci)],
         };
 
         ci.documentation = Some(lsp_types::Documentation::MarkupContent(MarkupContent {
@@ -455,6 +875,353 @@ impl Server {
         vec![response]
     }
 
+    fn handle_goto_definition_request(&mut self, request: Request) -> Vec<Message> {
+        let tdp = serde_json::from_value::<lsp_types::GotoDefinitionParams>(request.params)
+            .unwrap()
+            .text_document_position_params;
+
+        let uri = tdp.text_document.uri.to_string();
+        let content = self.open_files.get(&uri);
+        let document = parse_document(LocatedSpan::new(content));
+        let Some(target) = occurrence_at(&document, tdp.position) else {
+            return vec![response_empty(request.id)];
+        };
+        let (symbol, name) = (target.symbol, target.name().to_owned());
+
+        // prefer definitions in the same file, then look through the other open files
+        let mut locations = self.workspace_occurrences(symbol, &name, |o| o.definition);
+        locations.sort_by_key(|location| location.uri.as_str() != uri);
+        if let Some(first) = locations.first().map(|l| l.uri.clone()) {
+            locations.retain(|l| l.uri == first);
+        }
+        vec![response_ok(
+            request.id,
+            lsp_types::GotoDefinitionResponse::Array(locations),
+        )]
+    }
+
+    fn handle_references_request(&mut self, request: Request) -> Vec<Message> {
+        let rp = serde_json::from_value::<lsp_types::ReferenceParams>(request.params).unwrap();
+        let tdp = rp.text_document_position;
+
+        let content = self.open_files.get(tdp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let Some(target) = occurrence_at(&document, tdp.position) else {
+            return vec![response_empty(request.id)];
+        };
+        let (symbol, name) = (target.symbol, target.name().to_owned());
+
+        let include_declaration = rp.context.include_declaration;
+        let locations =
+            self.workspace_occurrences(symbol, &name, |o| include_declaration || !o.definition);
+        vec![response_ok(request.id, locations)]
+    }
+
+    fn handle_prepare_rename_request(&mut self, request: Request) -> Vec<Message> {
+        let tdp = serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)
+            .unwrap();
+
+        let content = self.open_files.get(tdp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        match occurrences(&document)
+            .into_iter()
+            .find(|o| o.contains(tdp.position))
+        {
+            Some(occurrence) => vec![response_ok(
+                request.id,
+                lsp_types::PrepareRenameResponse::RangeWithPlaceholder {
+                    range: span_range(&occurrence.span),
+                    placeholder: occurrence.name().to_owned(),
+                },
+            )],
+            None => vec![response_empty(request.id)],
+        }
+    }
+
+    fn handle_rename_request(&mut self, request: Request) -> Vec<Message> {
+        let rp = serde_json::from_value::<lsp_types::RenameParams>(request.params).unwrap();
+        let tdp = rp.text_document_position;
+
+        let content = self.open_files.get(tdp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let Some(target) = occurrences(&document)
+            .into_iter()
+            .find(|o| o.contains(tdp.position))
+        else {
+            return vec![response_err(
+                request.id,
+                ErrorCode::InvalidParams as i32,
+                "No time zone ID or UID to rename here".to_owned(),
+            )];
+        };
+        let (symbol, name) = (target.symbol, target.name().to_owned());
+
+        // the new name is written out as-is, so it can't contain anything that would need quoting
+        // or escaping
+        if rp.new_name.is_empty()
+            || rp
+                .new_name
+                .contains(|c: char| c.is_control() || matches!(c, '"' | ';' | ':' | ',' | '\\'))
+        {
+            return vec![response_err(
+                request.id,
+                ErrorCode::InvalidParams as i32,
+                format!("Invalid name {:?}", rp.new_name),
+            )];
+        }
+
+        let mut changes = std::collections::HashMap::<Url, Vec<TextEdit>>::new();
+        for location in self.workspace_occurrences(symbol, &name, |_| true) {
+            changes.entry(location.uri).or_default().push(TextEdit {
+                range: location.range,
+                new_text: rp.new_name.clone(),
+            });
+        }
+        vec![response_ok(
+            request.id,
+            lsp_types::WorkspaceEdit {
+                changes: Some(changes),
+                ..Default::default()
+            },
+        )]
+    }
+
+    /// Find the occurrences of an identifier across all of the open files.
+    fn workspace_occurrences(
+        &self,
+        symbol: Symbol,
+        name: &str,
+        filter: impl Fn(&Occurrence) -> bool,
+    ) -> Vec<lsp_types::Location> {
+        let mut locations = Vec::new();
+        for (file, content) in self.open_files.iter() {
+            let Ok(uri) = Url::parse(file) else {
+                continue;
+            };
+            let document = parse_document(LocatedSpan::new(content));
+            for occurrence in occurrences(&document) {
+                if occurrence.symbol == symbol && occurrence.name() == name && filter(&occurrence) {
+                    locations.push(lsp_types::Location {
+                        uri: uri.clone(),
+                        range: span_range(&occurrence.span),
+                    });
+                }
+            }
+        }
+        locations
+    }
+
+    fn handle_document_symbol_request(&mut self, request: Request) -> Vec<Message> {
+        let dsp =
+            serde_json::from_value::<lsp_types::DocumentSymbolParams>(request.params).unwrap();
+
+        let content = self.open_files.get(dsp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let symbols: Vec<_> = document
+            .components
+            .iter()
+            .map(|c| document_symbol(&document, c))
+            .collect();
+        vec![response_ok(
+            request.id,
+            lsp_types::DocumentSymbolResponse::Nested(symbols),
+        )]
+    }
+
+    fn handle_folding_range_request(&mut self, request: Request) -> Vec<Message> {
+        let frp = serde_json::from_value::<lsp_types::FoldingRangeParams>(request.params).unwrap();
+
+        let content = self.open_files.get(frp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let components = document
+            .all_components()
+            .into_iter()
+            .map(|c| component_range(&document, c));
+        // folded lines of a single property
+        let properties = document.properties.iter().map(property_range);
+        let ranges: Vec<_> = components
+            .chain(properties)
+            .filter(|range| range.end.line > range.start.line)
+            .map(|range| FoldingRange {
+                start_line: range.start.line,
+                end_line: range.end.line,
+                kind: Some(FoldingRangeKind::Region),
+                ..Default::default()
+            })
+            .collect();
+        vec![response_ok(request.id, ranges)]
+    }
+
+    fn handle_semantic_tokens_full_request(&mut self, request: Request) -> Vec<Message> {
+        let stp =
+            serde_json::from_value::<lsp_types::SemanticTokensParams>(request.params).unwrap();
+
+        let content = self.open_files.get(stp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let tokens = semantic_tokens::tokens(&document);
+        vec![response_ok(
+            request.id,
+            lsp_types::SemanticTokens {
+                result_id: None,
+                data: semantic_tokens::encode(&tokens),
+            },
+        )]
+    }
+
+    fn handle_semantic_tokens_range_request(&mut self, request: Request) -> Vec<Message> {
+        let strp =
+            serde_json::from_value::<lsp_types::SemanticTokensRangeParams>(request.params).unwrap();
+
+        let content = self.open_files.get(strp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let tokens: Vec<_> = semantic_tokens::tokens(&document)
+            .into_iter()
+            .filter(|t| strp.range.start.line <= t.line && t.line <= strp.range.end.line)
+            .collect();
+        vec![response_ok(
+            request.id,
+            lsp_types::SemanticTokens {
+                result_id: None,
+                data: semantic_tokens::encode(&tokens),
+            },
+        )]
+    }
+
+    fn handle_document_link_request(&mut self, request: Request) -> Vec<Message> {
+        let dlp = serde_json::from_value::<lsp_types::DocumentLinkParams>(request.params).unwrap();
+
+        let content = self.open_files.get(dlp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let links: Vec<_> = links(&document)
+            .into_iter()
+            .filter_map(|link| {
+                Some(lsp_types::DocumentLink {
+                    range: span_range(&link.span),
+                    target: Some(link.target().ok()?),
+                    tooltip: None,
+                    data: None,
+                })
+            })
+            .collect();
+        vec![response_ok(request.id, links)]
+    }
+
+    fn handle_linked_editing_range_request(&mut self, request: Request) -> Vec<Message> {
+        let lerp =
+            serde_json::from_value::<lsp_types::LinkedEditingRangeParams>(request.params).unwrap();
+        let tdp = lerp.text_document_position_params;
+
+        let content = self.open_files.get(tdp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        match component_name_ranges(&document, tdp.position) {
+            Some(ranges) => vec![response_ok(
+                request.id,
+                lsp_types::LinkedEditingRanges {
+                    ranges: ranges.to_vec(),
+                    word_pattern: Some("[A-Za-z0-9-]+".to_owned()),
+                },
+            )],
+            None => vec![response_empty(request.id)],
+        }
+    }
+
+    fn handle_inlay_hint_request(&mut self, request: Request) -> Vec<Message> {
+        let ihp = serde_json::from_value::<lsp_types::InlayHintParams>(request.params).unwrap();
+
+        let content = self.open_files.get(ihp.text_document.uri.as_ref());
+        let document = parse_document(LocatedSpan::new(content));
+        let hints: Vec<_> = document
+            .properties
+            .iter()
+            .filter_map(|property| {
+                let position = span_range(&property.value?).end;
+                if position < ihp.range.start || ihp.range.end < position {
+                    return None;
+                }
+                Some(lsp_types::InlayHint {
+                    position,
+                    label: lsp_types::InlayHintLabel::String(inlay_hint_label(property)?),
+                    kind: None,
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: Some(true),
+                    padding_right: None,
+                    data: None,
+                })
+            })
+            .collect();
+        vec![response_ok(request.id, hints)]
+    }
+
+    fn handle_code_action_request(&mut self, request: Request) -> Vec<Message> {
+        let cap = serde_json::from_value::<lsp_types::CodeActionParams>(request.params).unwrap();
+
+        let actions: Vec<_> = cap
+            .context
+            .diagnostics
+            .iter()
+            .filter_map(|diagnostic| {
+                let fix = serde_json::from_value::<Fix>(diagnostic.data.clone()?).ok()?;
+                Some(lsp_types::CodeActionOrCommand::CodeAction(
+                    lsp_types::CodeAction {
+                        title: fix.title,
+                        kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+                        diagnostics: Some(vec![diagnostic.clone()]),
+                        edit: Some(lsp_types::WorkspaceEdit {
+                            changes: Some(
+                                [(cap.text_document.uri.clone(), fix.edits)]
+                                    .into_iter()
+                                    .collect(),
+                            ),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                ))
+            })
+            .collect();
+        vec![response_ok(request.id, actions)]
+    }
+
+    fn handle_formatting_request(&mut self, request: Request) -> Vec<Message> {
+        let dfp =
+            serde_json::from_value::<lsp_types::DocumentFormattingParams>(request.params).unwrap();
+
+        let config = self.config(dfp.text_document.uri.as_ref());
+        let content = self.open_files.get(dfp.text_document.uri.as_ref());
+        let formatted = match format::format(content, &config) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                return vec![response_err(
+                    request.id,
+                    ErrorCode::RequestFailed as i32,
+                    format!("Failed to format: {err}"),
+                )]
+            }
+        };
+        if formatted == content {
+            return vec![response_ok(request.id, Vec::<TextEdit>::new())];
+        }
+        // replace the whole document
+        let end = Position {
+            line: content.matches('\n').count() as u32,
+            character: content
+                .rsplit('\n')
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .count() as u32,
+        };
+        let edit = TextEdit {
+            range: Range {
+                start: Position::default(),
+                end,
+            },
+            new_text: formatted,
+        };
+        vec![response_ok(request.id, vec![edit])]
+    }
+
     fn handle_did_open_text_document_notification(
         &mut self,
         notification: Notification,
@@ -525,127 +1292,505 @@ impl Server {
         // );
     }
 
+    fn handle_did_change_configuration_notification(
+        &mut self,
+        c: &Connection,
+        notification: Notification,
+    ) -> Vec<Message> {
+        let dccp =
+            serde_json::from_value::<lsp_types::DidChangeConfigurationParams>(notification.params)
+                .unwrap();
+        // settings may be given under the server's name or directly
+        let settings = match dccp.settings.get("icalls") {
+            Some(settings) => settings.clone(),
+            None => dccp.settings,
+        };
+        match serde_json::from_value::<InitializationOptions>(settings) {
+            Ok(options) => {
+                for problem in options.config.problems() {
+                    show_warning(c, problem);
+                }
+                self.options = options;
+            }
+            Err(err) => {
+                show_warning(c, format!("Invalid configuration: {err}"));
+                return Vec::new();
+            }
+        }
+        self.reload_configs()
+    }
+
+    fn handle_did_change_watched_files_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Vec<Message> {
+        let dcwfp =
+            serde_json::from_value::<lsp_types::DidChangeWatchedFilesParams>(notification.params)
+                .unwrap();
+        if dcwfp.changes.iter().any(|change| is_config(&change.uri)) {
+            self.reload_configs()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn handle_did_save_text_document_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Vec<Message> {
+        let dstdp =
+            serde_json::from_value::<lsp_types::DidSaveTextDocumentParams>(notification.params)
+                .unwrap();
+        if is_config(&dstdp.text_document.uri) {
+            self.reload_configs()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Forget the loaded config files, and publish the diagnostics of the open files again with
+    /// the new configs.
+    fn reload_configs(&mut self) -> Vec<Message> {
+        self.configs.clear();
+        // show any problems with config files again
+        self.config_problems.clear();
+        let files = self
+            .open_files
+            .iter()
+            .map(|(uri, _)| uri.to_owned())
+            .collect::<Vec<_>>();
+        files
+            .into_iter()
+            .filter_map(|file| {
+                let diagnostics = self.refresh_diagnostics(&file);
+                let uri = Url::parse(&file).ok()?;
+                Some(Message::Notification(Notification::new(
+                    PublishDiagnostics::METHOD.to_owned(),
+                    PublishDiagnosticsParams {
+                        uri,
+                        diagnostics,
+                        version: None,
+                    },
+                )))
+            })
+            .collect()
+    }
+
     fn refresh_diagnostics(&mut self, file: &str) -> Vec<Diagnostic> {
+        let config = self.config(file);
         let content = self.open_files.get(file);
-        let (_, ast) = match parse_properties(LocatedSpan::new(content)) {
-            Ok(ast) => ast,
-            Err(nom::Err::Error(err)) => {
-                return vec![Diagnostic {
-                    range: Range {
-                        start: Position {
-                            line: err.input.location_line(),
-                            character: 0,
-                        },
-                        end: Position {
-                            line: err.input.location_line(),
-                            character: 0,
-                        },
-                    },
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    message: err.to_string(),
-                    ..Default::default()
-                }]
+        let uri = Url::parse(file).ok();
+        document_diagnostics(uri.as_ref(), content, &config)
+    }
+}
+
+/// Collect the calendar files under a path, in a stable order.
+fn calendar_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return Ok(());
+    }
+    let mut entries = std::fs::read_dir(path)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            calendar_files(&entry, files)?;
+        } else if entry
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("ics"))
+        {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Configs for the files given on the command line, discovered once per directory and merged with
+/// the command line flags.
+struct Configs {
+    root: Option<PathBuf>,
+    flags: Config,
+    by_dir: HashMap<PathBuf, Option<Config>>,
+    reported: HashSet<String>,
+}
+
+impl Configs {
+    fn new(flags: Config) -> Self {
+        Self {
+            root: std::env::current_dir().ok(),
+            flags,
+            by_dir: HashMap::new(),
+            reported: HashSet::new(),
+        }
+    }
+
+    /// The config for a file, given by its canonical path, or `None` if it couldn't be loaded,
+    /// after reporting why.
+    fn get(&mut self, file: Option<&Path>) -> Option<&Config> {
+        let dir = file
+            .and_then(|f| f.parent())
+            .unwrap_or(Path::new("."))
+            .to_owned();
+        let Self {
+            root,
+            flags,
+            by_dir,
+            reported,
+        } = self;
+        by_dir
+            .entry(dir)
+            .or_insert_with_key(|dir| match config::discover(dir, root.as_deref()) {
+                Ok(mut config) => {
+                    for problem in config.problems() {
+                        if reported.insert(problem.clone()) {
+                            eprintln!("warning: {problem} in {}", config::FILE_NAME);
+                        }
+                    }
+                    config.merge(flags.clone());
+                    Some(config)
+                }
+                Err(err) => {
+                    if reported.insert(err.to_string()) {
+                        eprintln!("error: {err}");
+                    }
+                    None
+                }
+            })
+            .as_ref()
+    }
+}
+
+/// Stop quietly when whatever reads the output has gone away, e.g. `head`, and fail on any other
+/// error writing it.
+fn written(result: std::io::Result<()>) {
+    match result {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(err) => {
+            eprintln!("error: could not write output: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn check(args: CheckArgs) -> bool {
+    let mut out = std::io::stdout().lock();
+    let mut failed = false;
+    let flags = Config {
+        diagnostics: diagnostics::Options {
+            check_line_endings: args.allow_lf.then_some(false),
+            check_line_length: args.max_line_length.map(|m| m > 0),
+            max_line_length: args.max_line_length,
+            rules: Default::default(),
+        },
+        ..Default::default()
+    };
+    let mut configs = Configs::new(flags);
+    let mut files = Vec::new();
+    for path in &args.paths {
+        if let Err(err) = calendar_files(path, &mut files) {
+            eprintln!("error: could not read {}: {err}", path.display());
+            failed = true;
+        }
+    }
+    let mut counts = [0; 4];
+    let mut sarif_files = Vec::new();
+    for file in &files {
+        let content = match std::fs::read(file) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(err) => {
+                eprintln!("error: could not read {}: {err}", file.display());
+                failed = true;
+                continue;
             }
-            Err(nom::Err::Failure(err)) => {
-                return vec![Diagnostic {
-                    range: Range {
-                        start: Position {
-                            line: err.input.location_line(),
-                            character: 0,
-                        },
-                        end: Position {
-                            line: err.input.location_line(),
-                            character: 0,
-                        },
-                    },
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    message: err.to_string(),
-                    ..Default::default()
-                }]
+        };
+        let canonical = file.canonicalize().ok();
+        let Some(config) = configs.get(canonical.as_deref()) else {
+            failed = true;
+            continue;
+        };
+        let uri = canonical.and_then(|f| Url::from_file_path(f).ok());
+        let path = file.display().to_string();
+        let mut diagnostics = document_diagnostics(uri.as_ref(), &content, config);
+        diagnostics.retain(|d| Severity::of(d) >= args.min_severity);
+        for diagnostic in &diagnostics {
+            counts[Severity::of(diagnostic) as usize] += 1;
+            match args.format {
+                Format::Human => written(writeln!(
+                    out,
+                    "{}",
+                    report::render(&path, &content, diagnostic)
+                )),
+                Format::Json => written(writeln!(out, "{}", report::json_line(&path, diagnostic))),
+                Format::Sarif => {}
             }
-            Err(nom::Err::Incomplete(_)) => {
-                unreachable!()
+        }
+        if args.format == Format::Sarif {
+            sarif_files.push((path, diagnostics));
+        }
+    }
+    if args.format == Format::Sarif {
+        written(writeln!(
+            out,
+            "{}",
+            serde_json::to_string_pretty(&report::sarif(&sarif_files)).unwrap()
+        ));
+    }
+    let [hints, infos, warnings, errors] = counts;
+    let summary = [
+        (errors, "error"),
+        (warnings, "warning"),
+        (infos, "info"),
+        (hints, "hint"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, label)| format!("{n} {label}{}", if *n == 1 { "" } else { "s" }))
+    .collect::<Vec<_>>();
+    if args.format == Format::Human && !summary.is_empty() {
+        eprintln!(
+            "checked {} file{}: {}",
+            files.len(),
+            if files.len() == 1 { "" } else { "s" },
+            summary.join(", ")
+        );
+    }
+    failed || errors > 0
+}
+
+fn print_diff(path: &str, content: &str, formatted: &str) {
+    written(write!(
+        std::io::stdout().lock(),
+        "{}",
+        similar::TextDiff::from_lines(content, formatted)
+            .unified_diff()
+            .header(path, path)
+    ));
+}
+
+fn fmt(args: FmtArgs) -> bool {
+    let mut configs = Configs::new(Config::default());
+    if args.paths.is_empty() {
+        let mut content = String::new();
+        if let Err(err) = std::io::stdin().read_to_string(&mut content) {
+            eprintln!("error: could not read stdin: {err}");
+            return true;
+        }
+        let Some(config) = configs.get(None) else {
+            return true;
+        };
+        return match format::format(&content, config) {
+            Ok(formatted) if args.check => {
+                print_diff("<stdin>", &content, &formatted);
+                formatted != content
+            }
+            Ok(formatted) => {
+                written(write!(std::io::stdout().lock(), "{formatted}"));
+                false
+            }
+            Err(err) => {
+                eprintln!("error: could not format <stdin>: {err}");
+                true
             }
         };
-        let mut diagnostics = Vec::new();
-        for property in ast {
-            if property.name.is_none() {
-                let line = property.name_raw.location_line() - 1;
-                let character_start = property.name_raw.get_utf8_column() - 1;
-                let character_end = character_start + property.name_raw.fragment().len();
-                diagnostics.push(Diagnostic {
-                    range: lsp_types::Range {
-                        start: lsp_types::Position {
-                            line,
-                            character: character_start as u32,
-                        },
-                        end: lsp_types::Position {
-                            line,
-                            character: character_end as u32,
-                        },
-                    },
-                    severity: Some(DiagnosticSeverity::WARNING),
-                    message: format!("Unknown property {:?}", property.name_raw.fragment()),
-                    ..Default::default()
-                });
-            } else if let Some(value) = property.value {
-                let line = value.location_line() - 1;
-                let character_start = value.get_utf8_column() - 1;
-                let character_end = character_start + value.fragment().len();
-                if let Err(e) = property.check_value_type() {
-                    diagnostics.push(Diagnostic {
-                        range: lsp_types::Range {
-                            start: lsp_types::Position {
-                                line,
-                                character: character_start as u32,
-                            },
-                            end: lsp_types::Position {
-                                line,
-                                character: character_end as u32,
-                            },
-                        },
-                        severity: Some(DiagnosticSeverity::WARNING),
-                        message: format!(
-                            "Failed to match expected type: {:?}\n\n{}",
-                            property.name.unwrap().to_property().value_type(),
-                            e
-                        ),
-                        ..Default::default()
-                    });
-                }
+    }
+
+    let mut failed = false;
+    let mut files = Vec::new();
+    for path in &args.paths {
+        if let Err(err) = calendar_files(path, &mut files) {
+            eprintln!("error: could not read {}: {err}", path.display());
+            failed = true;
+        }
+    }
+    let mut unformatted = 0;
+    for file in &files {
+        let path = file.display().to_string();
+        // not lossy, so that formatting doesn't replace anything it couldn't read
+        let content = match std::fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("error: could not read {path}: {err}");
+                failed = true;
+                continue;
+            }
+        };
+        let Some(config) = configs.get(file.canonicalize().ok().as_deref()) else {
+            failed = true;
+            continue;
+        };
+        let formatted = match format::format(&content, config) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                eprintln!("error: could not format {path}: {err}");
+                failed = true;
+                continue;
             }
+        };
+        if formatted == content {
+            continue;
+        }
+        if args.check {
+            print_diff(&path, &content, &formatted);
+            unformatted += 1;
+        } else if let Err(err) = std::fs::write(file, formatted) {
+            eprintln!("error: could not write {path}: {err}");
+            failed = true;
+        }
+    }
+    if unformatted > 0 {
+        eprintln!(
+            "{unformatted} of {} file{} not formatted",
+            files.len(),
+            if files.len() == 1 { "" } else { "s" }
+        );
+    }
+    failed || unformatted > 0
+}
 
-            for parameter in property.params {
-                if parameter.name.is_none() {
-                    let line = parameter.name_raw.location_line() - 1;
-                    let character_start = parameter.name_raw.get_utf8_column() - 1;
-                    let character_end = character_start + parameter.name_raw.fragment().len();
-                    diagnostics.push(Diagnostic {
-                        range: lsp_types::Range {
-                            start: lsp_types::Position {
-                                line,
-                                character: character_start as u32,
-                            },
-                            end: lsp_types::Position {
-                                line,
-                                character: character_end as u32,
-                            },
-                        },
-                        severity: Some(DiagnosticSeverity::WARNING),
-                        message: format!("Unknown parameter {:?}", parameter.name_raw.fragment()),
-                        ..Default::default()
-                    });
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn expand(args: ExpandArgs) -> bool {
+    let mut failed = false;
+    let mut configs = Configs::new(Config::default());
+    let tz = args
+        .timezone
+        .or_else(|| configs.get(None)?.timezone())
+        .unwrap_or(chrono_tz::UTC);
+    let local = |naive: chrono::NaiveDateTime| match naive.and_local_timezone(tz) {
+        chrono::LocalResult::Single(time) | chrono::LocalResult::Ambiguous(time, _) => time,
+        chrono::LocalResult::None => naive.and_utc().with_timezone(&tz),
+    };
+    let (from, to) = (local(args.from), local(args.to));
+    if to <= from {
+        eprintln!("error: --to must be later than --from");
+        return true;
+    }
+
+    let mut files = Vec::new();
+    for path in &args.paths {
+        if let Err(err) = calendar_files(path, &mut files) {
+            eprintln!("error: could not read {}: {err}", path.display());
+            failed = true;
+        }
+    }
+    let mut occurrences = Vec::new();
+    for file in &files {
+        let content = match std::fs::read(file) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(err) => {
+                eprintln!("error: could not read {}: {err}", file.display());
+                failed = true;
+                continue;
+            }
+        };
+        let document = parse_document(LocatedSpan::new(&content));
+        let path = file.display().to_string();
+        occurrences.extend(
+            expand::occurrences(&document, from, to)
+                .into_iter()
+                .map(|o| (path.clone(), o)),
+        );
+    }
+    occurrences.sort_by_key(|(_, o)| o.start.instant(tz));
+
+    let header = [
+        "start",
+        "end",
+        "component",
+        "uid",
+        "summary",
+        "source",
+        "path",
+    ];
+    let rows = occurrences
+        .iter()
+        .map(|(path, o)| {
+            [
+                o.start.in_timezone(tz).to_string(),
+                o.end.in_timezone(tz).to_string(),
+                o.component.clone(),
+                o.uid.clone().unwrap_or_default(),
+                o.summary.clone().unwrap_or_default(),
+                o.source.name().to_owned(),
+                path.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut out = std::io::stdout().lock();
+    match args.format {
+        ExpandFormat::Table => {
+            let widths = header.map(|h| h.len());
+            let widths = rows.iter().fold(widths, |mut widths, row| {
+                for (width, field) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(field.chars().count());
                 }
+                widths
+            });
+            let header = header.map(str::to_uppercase);
+            for row in std::iter::once(&header).chain(&rows) {
+                let line = row
+                    .iter()
+                    .zip(widths)
+                    .map(|(field, width)| format!("{field:width$}"))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                written(writeln!(out, "{}", line.trim_end()));
+            }
+        }
+        ExpandFormat::Json => {
+            for row in &rows {
+                let object = header
+                    .iter()
+                    .zip(row)
+                    .map(|(name, field)| (name.to_string(), serde_json::json!(field)))
+                    .collect::<serde_json::Map<_, _>>();
+                written(writeln!(out, "{}", serde_json::Value::Object(object)));
+            }
+        }
+        ExpandFormat::Csv => {
+            for row in std::iter::once(header.map(str::to_owned)).chain(rows) {
+                written(writeln!(out, "{}", row.map(|f| csv_field(&f)).join(",")));
             }
         }
-
-        diagnostics
     }
+    failed
 }
 
 fn main() {
     let args = Args::parse();
-    let (p, c, io) = connect(args.stdio);
+    match args.command {
+        Some(Command::Check(check_args)) => {
+            let failed = check(check_args);
+            std::process::exit(if failed { 1 } else { 0 });
+        }
+        Some(Command::Fmt(fmt_args)) => {
+            let failed = fmt(fmt_args);
+            std::process::exit(if failed { 1 } else { 0 });
+        }
+        Some(Command::Expand(expand_args)) => {
+            let failed = expand(expand_args);
+            std::process::exit(if failed { 1 } else { 0 });
+        }
+        None => {}
+    }
+    if !args.stdio {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "no connection mode given, e.g. --stdio, or a subcommand",
+            )
+            .exit();
+    }
+    let (p, c, io) = connect();
     let server = Server::new(&c, p);
     let s = server.serve(c);
     io.join().unwrap();
@@ -658,11 +1803,135 @@ fn main() {
     }
 }
 
+fn document_symbol(document: &ast::Document, component: &ast::Component) -> DocumentSymbol {
+    let kind_name = component.name.fragment().to_uppercase();
+    let property_value = |name| {
+        component
+            .property(document, name)
+            .and_then(|p| p.value)
+            .map(|v| unfold(v.fragment()).into_owned())
+    };
+    let (name, kind) = match kind_name.as_str() {
+        "VEVENT" | "VTODO" | "VJOURNAL" => {
+            let summary = property_value(PropertyName::Summary).unwrap_or(kind_name.clone());
+            let start = component
+                .property(document, PropertyName::DtStart)
+                .and_then(|p| p.parse_value());
+            let name = match start {
+                Some(start) => format!("{summary} ({})", start.prettify()),
+                None => summary,
+            };
+            let kind = match kind_name.as_str() {
+                "VEVENT" => SymbolKind::EVENT,
+                "VTODO" => SymbolKind::OBJECT,
+                _ => SymbolKind::STRING,
+            };
+            (name, kind)
+        }
+        "VTIMEZONE" => (
+            property_value(PropertyName::TzId).unwrap_or(kind_name.clone()),
+            SymbolKind::NAMESPACE,
+        ),
+        "VALARM" => (
+            match property_value(PropertyName::Action) {
+                Some(action) => format!("{kind_name} {action}"),
+                None => kind_name.clone(),
+            },
+            SymbolKind::KEY,
+        ),
+        "VCALENDAR" => (kind_name.clone(), SymbolKind::MODULE),
+        _ => (kind_name.clone(), SymbolKind::STRUCT),
+    };
+    let children: Vec<_> = component
+        .children
+        .iter()
+        .map(|c| document_symbol(document, c))
+        .collect();
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail: Some(kind_name),
+        kind,
+        tags: None,
+        deprecated: None,
+        range: component_range(document, component),
+        selection_range: span_range(&component.name),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+/// A readable form of a property's value, shown after it.
+fn inlay_hint_label(property: &ast::Property) -> Option<String> {
+    let value = property.parse_value()?;
+    let label = match (property.name?, &value) {
+        (_, Value::Date(date)) => date.short(),
+        (_, Value::DateTime(date, time)) => {
+            let zone = if time.utc {
+                Some("UTC")
+            } else {
+                tzid_param(property).map(|tzid| tzid.fragment().trim_matches('"'))
+            };
+            match zone {
+                Some(zone) => format!("{} {} {zone}", date.short(), time.short()),
+                None => format!("{} {}", date.short(), time.short()),
+            }
+        }
+        (PropertyName::Trigger, Value::Duration(duration)) => {
+            let related = match property.param(ParameterName::Related).and_then(|p| p.value) {
+                Some(related) if related.fragment().eq_ignore_ascii_case("END") => "end",
+                _ => "start",
+            };
+            match duration.as_seconds() {
+                0 => format!("at {related}"),
+                s if s < 0 => format!("{} before {related}", duration.describe()),
+                _ => format!("{} after {related}", duration.describe()),
+            }
+        }
+        (_, Value::Duration(_) | Value::RecurrenceRule(_) | Value::UtcOffset(_)) => {
+            value.prettify()
+        }
+        _ => return None,
+    };
+    Some(label)
+}
+
+fn matches_keyword(keywords: Vec<&str>, lower_word: &str) -> bool {
+    keywords
+        .iter()
+        .any(|kw| kw.to_lowercase().contains(lower_word))
+}
+
+/// Documentation for a completion item, given up front for definitions from the config as they
+/// can't be found again when resolving the item.
+fn completion_documentation(
+    from_config: bool,
+    kind: SyntaxKind,
+    render: impl FnOnce() -> String,
+) -> CompletionItem {
+    if from_config {
+        CompletionItem {
+            documentation: Some(lsp_types::Documentation::MarkupContent(MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: render(),
+            })),
+            ..Default::default()
+        }
+    } else {
+        CompletionItem {
+            data: Some(serde_json::to_value(kind).unwrap()),
+            ..Default::default()
+        }
+    }
+}
+
 fn render_property(property: &dyn Property) -> String {
     let mut lines = Vec::new();
     lines.push(format!("# {}", property.name()));
     lines.push(format!("_{:?}_", property.value_type()));
     lines.push(property.purpose().to_owned());
+    if !property.components().is_empty() {
+        lines.push(format!("Allowed in {}", property.components().join(", ")));
+    }
     if !property.examples().is_empty() {
         let mut examples = Vec::new();
         examples.push("## Examples\n".to_owned());
@@ -706,9 +1975,94 @@ mod tests {
 
             This property defines a short summary or subject for the calendar component.
 
+            Allowed in VEVENT, VTODO, VJOURNAL, VALARM
+
             ## Examples
 
             - SUMMARY:Department Party"#]]
         .assert_eq(&render_property(&Summary));
     }
+
+    #[test]
+    fn symbols() {
+        let document = parse_document(LocatedSpan::new(
+            "BEGIN:VCALENDAR
+BEGIN:VTIMEZONE
+TZID:Europe/London
+END:VTIMEZONE
+BEGIN:VEVENT
+SUMMARY:Department Party
+DTSTART;VALUE=DATE:20221008
+BEGIN:VALARM
+ACTION:DISPLAY
+END:VALARM
+END:VEVENT
+END:VCALENDAR
+",
+        ));
+        fn names(symbol: &DocumentSymbol, depth: usize, out: &mut Vec<String>) {
+            out.push(format!(
+                "{}{} {:?} {}-{}",
+                "  ".repeat(depth),
+                symbol.name,
+                symbol.kind,
+                symbol.range.start.line,
+                symbol.range.end.line
+            ));
+            for child in symbol.children.iter().flatten() {
+                names(child, depth + 1, out);
+            }
+        }
+        let mut out = Vec::new();
+        names(
+            &document_symbol(&document, &document.components[0]),
+            0,
+            &mut out,
+        );
+        expect![[r#"
+            [
+                "VCALENDAR Module 0-11",
+                "  Europe/London Namespace 1-3",
+                "  Department Party (8th October 2022) Event 4-10",
+                "    VALARM DISPLAY Key 7-9",
+            ]
+        "#]]
+        .assert_debug_eq(&out);
+    }
+
+    #[test]
+    fn inlay_hints() {
+        let document = parse_document(LocatedSpan::new(
+            "BEGIN:VEVENT
+DTSTART;TZID=Europe/London:20221008T170000
+DTEND:20221008T183000Z
+DURATION:PT1H30M
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20230301
+BEGIN:VALARM
+TRIGGER:-PT15M
+TRIGGER;RELATED=END:PT0S
+END:VALARM
+TZOFFSETTO:-0500
+SUMMARY:Department Party
+END:VEVENT
+",
+        ));
+        let labels = document
+            .properties
+            .iter()
+            .filter_map(inlay_hint_label)
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                "Sat 8 Oct 2022 17:00 Europe/London",
+                "Sat 8 Oct 2022 18:30 UTC",
+                "1h 30m",
+                "weekly on Mon, Wed until 1 Mar 2023",
+                "15 minutes before start",
+                "at end",
+                "UTC-05:00",
+            ]
+        "#]]
+        .assert_debug_eq(&labels);
+    }
 }
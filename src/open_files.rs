@@ -9,8 +9,12 @@ pub struct OpenFiles {
 
 impl OpenFiles {
     fn load(&mut self, uri: &str) {
-        let content = std::fs::read_to_string(uri).unwrap();
-        self.add(uri.to_string(), content);
+        let content = std::fs::read(uri).unwrap();
+        // replace invalid UTF-8 rather than failing to load, the replacement characters are reported
+        self.add(
+            uri.to_string(),
+            String::from_utf8_lossy(&content).into_owned(),
+        );
     }
 
     pub fn add(&mut self, uri: String, content: String) {
@@ -38,6 +42,12 @@ impl OpenFiles {
         }
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.files
+            .iter()
+            .map(|(uri, content)| (uri.as_str(), content.as_str()))
+    }
+
     pub fn remove(&mut self, uri: &str) {
         self.files.remove(uri);
     }
@@ -1,14 +1,18 @@
+use std::str::FromStr as _;
+
 use strum::IntoEnumIterator as _;
 
 use crate::{ast, value::ValueType};
 
 pub trait Parameter {
-    fn name(&self) -> &'static str;
-    fn purpose(&self) -> &'static str;
+    fn name(&self) -> &str;
+    fn purpose(&self) -> &str;
     fn value_type(&self) -> ValueType;
-    fn description(&self) -> &'static str;
-    fn examples(&self) -> Vec<&'static str>;
-    fn keywords(&self) -> Vec<&'static str>;
+    fn description(&self) -> &str;
+    fn examples(&self) -> Vec<&str>;
+    fn keywords(&self) -> Vec<&str>;
+    /// The values allowed for the parameter, empty if the value isn't enumerated.
+    fn enumerated_values(&self) -> Vec<&str>;
 }
 
 pub fn parameters() -> Vec<&'static dyn Parameter> {
@@ -29,6 +33,11 @@ macro_rules! parameter {
             fn keywords(&self) -> Vec<&'static str> {
                 vec![$($kw),+]
             }
+            fn enumerated_values(&self) -> Vec<&'static str> {
+                ast::ParameterName::from_str($name)
+                    .map(|name| enumerated_values(name).to_vec())
+                    .unwrap_or_default()
+            }
         }
     };
 }
@@ -67,7 +76,7 @@ parameter! {
     DelegatedFrom,
     "DELEGATED-FROM",
     "",
-    ValueType::Text,
+    ValueType::CalAddress,
     "",
     vec![],
     "delegated-from"
@@ -77,7 +86,7 @@ parameter! {
     DelegatedTo,
     "DELEGATED-TO",
     "",
-    ValueType::Text,
+    ValueType::CalAddress,
     "",
     vec![],
     "delegated-to"
@@ -87,7 +96,7 @@ parameter! {
     Dir,
     "DIR",
     "",
-    ValueType::Text,
+    ValueType::Uri,
     "",
     vec![],
     "dir"
@@ -137,7 +146,7 @@ parameter! {
     Member,
     "MEMBER",
     "",
-    ValueType::Text,
+    ValueType::CalAddress,
     "",
     vec![],
     "member"
@@ -207,7 +216,7 @@ parameter! {
     SentBy,
     "SENT-BY",
     "",
-    ValueType::Text,
+    ValueType::CalAddress,
     "",
     vec![],
     "sent-by"
@@ -232,3 +241,33 @@ parameter! {
     vec![],
     "value"
 }
+
+/// The values allowed for a parameter with an enumerated value.
+pub fn enumerated_values(name: ast::ParameterName) -> &'static [&'static str] {
+    use ast::ParameterName as P;
+    match name {
+        P::CUType => &["INDIVIDUAL", "GROUP", "RESOURCE", "ROOM", "UNKNOWN"],
+        P::Encoding => &["8BIT", "BASE64"],
+        P::FBType => &["FREE", "BUSY", "BUSY-UNAVAILABLE", "BUSY-TENTATIVE"],
+        P::PartStat => &[
+            "NEEDS-ACTION",
+            "ACCEPTED",
+            "DECLINED",
+            "TENTATIVE",
+            "DELEGATED",
+            "COMPLETED",
+            "IN-PROCESS",
+        ],
+        P::Range => &["THISANDFUTURE"],
+        P::Related => &["START", "END"],
+        P::RelType => &["PARENT", "CHILD", "SIBLING"],
+        P::Role => &[
+            "CHAIR",
+            "REQ-PARTICIPANT",
+            "OPT-PARTICIPANT",
+            "NON-PARTICIPANT",
+        ],
+        P::RSVP => &["TRUE", "FALSE"],
+        _ => &[],
+    }
+}
@@ -1,14 +1,21 @@
+use std::str::FromStr as _;
+
 use strum::IntoEnumIterator as _;
 
 use crate::{ast, value::ValueType};
 
 pub trait Property {
-    fn name(&self) -> &'static str;
-    fn purpose(&self) -> &'static str;
+    fn name(&self) -> &str;
+    fn purpose(&self) -> &str;
     fn value_type(&self) -> ValueType;
-    fn description(&self) -> &'static str;
-    fn examples(&self) -> Vec<&'static str>;
-    fn keywords(&self) -> Vec<&'static str>;
+    fn description(&self) -> &str;
+    fn examples(&self) -> Vec<&str>;
+    fn keywords(&self) -> Vec<&str>;
+    /// Components the property can be given in, empty if it isn't restricted.
+    fn components(&self) -> Vec<&str>;
+    /// The values allowed for the property in the given component, empty if the value isn't
+    /// enumerated.
+    fn enumerated_values(&self, component: &str) -> Vec<&str>;
 }
 
 pub fn properties() -> Vec<&'static dyn Property> {
@@ -29,6 +36,16 @@ macro_rules! property {
             fn keywords(&self) -> Vec<&'static str> {
                 vec![$($kw),+]
             }
+            fn components(&self) -> Vec<&'static str> {
+                ast::PropertyName::from_str($name)
+                    .map(|name| components(name).to_vec())
+                    .unwrap_or_default()
+            }
+            fn enumerated_values(&self, component: &str) -> Vec<&'static str> {
+                ast::PropertyName::from_str($name)
+                    .map(|name| enumerated_values(name, component).to_vec())
+                    .unwrap_or_default()
+            }
         }
     };
 }
@@ -220,7 +237,7 @@ property! {
     Attach,
     "ATTACH",
     "This property provides the capability to associate a document object with a calendar component.",
-    ValueType::Text,
+    ValueType::Uri,
     "",
     vec![ "ATTACH:CID:jsmith.part3.960817T083000.xyzMail@example.com",
        "ATTACH;FMTTYPE=application/postscript:ftp://example.com/pub/reports/r-960812.ps"
@@ -402,7 +419,7 @@ property! {
     TzOffsetFrom,
     "TZOFFSETFROM",
     "This property specifies the offset that is in use prior to this time zone observance.",
-    ValueType::Text,
+    ValueType::UtcOffset,
     "",
     vec![],
     "tzoffsetfrom"
@@ -412,7 +429,7 @@ property! {
     TzOffsetTo,
     "TZOFFSETTO",
     "This property specifies the offset that is in use in this time zone observance.",
-    ValueType::Text,
+    ValueType::UtcOffset,
     "",
     vec![],
     "tzoffsetto"
@@ -588,6 +605,122 @@ property! {
     "sequence"
 }
 
+/// The value types a property can have, the first being its default.
+pub fn value_types(name: ast::PropertyName) -> Vec<ValueType> {
+    use ast::PropertyName as P;
+    match name {
+        P::Attach => vec![ValueType::Uri, ValueType::Binary],
+        P::DtEnd | P::Due | P::DtStart | P::RecurrenceId | P::ExDate => {
+            vec![ValueType::DateTime, ValueType::Date]
+        }
+        P::RDate => vec![
+            ValueType::DateTime,
+            ValueType::Date,
+            ValueType::PeriodOfTime,
+        ],
+        P::Trigger => vec![ValueType::Duration, ValueType::DateTime],
+        _ => vec![name.to_property().value_type()],
+    }
+}
+
+/// The components a property can be given in, empty for `BEGIN` and `END`.
+pub fn components(name: ast::PropertyName) -> &'static [&'static str] {
+    use ast::PropertyName as P;
+    match name {
+        P::Begin | P::End => &[],
+        P::CalScale | P::Method | P::ProdId | P::Version => &["VCALENDAR"],
+        P::Attach | P::Description | P::Summary => &["VEVENT", "VTODO", "VJOURNAL", "VALARM"],
+        P::Categories
+        | P::Class
+        | P::Status
+        | P::RecurrenceId
+        | P::RelatedTo
+        | P::ExDate
+        | P::Created
+        | P::Sequence => &["VEVENT", "VTODO", "VJOURNAL"],
+        P::Comment => &[
+            "VEVENT",
+            "VTODO",
+            "VJOURNAL",
+            "VFREEBUSY",
+            "STANDARD",
+            "DAYLIGHT",
+        ],
+        P::Geo | P::Location | P::Priority | P::Resources => &["VEVENT", "VTODO"],
+        P::PercentComplete | P::Completed | P::Due => &["VTODO"],
+        P::DtEnd => &["VEVENT", "VFREEBUSY"],
+        P::DtStart => &[
+            "VEVENT",
+            "VTODO",
+            "VJOURNAL",
+            "VFREEBUSY",
+            "STANDARD",
+            "DAYLIGHT",
+        ],
+        P::Duration => &["VEVENT", "VTODO", "VALARM"],
+        P::FreeBusy => &["VFREEBUSY"],
+        P::Transp => &["VEVENT"],
+        P::TzId | P::TzUrl => &["VTIMEZONE"],
+        P::TzName | P::TzOffsetFrom | P::TzOffsetTo => &["STANDARD", "DAYLIGHT"],
+        P::Attendee => &["VEVENT", "VTODO", "VJOURNAL", "VFREEBUSY", "VALARM"],
+        P::Contact | P::Organizer | P::Url | P::Uid | P::DtStamp => {
+            &["VEVENT", "VTODO", "VJOURNAL", "VFREEBUSY"]
+        }
+        P::RDate | P::RRule => &["VEVENT", "VTODO", "VJOURNAL", "STANDARD", "DAYLIGHT"],
+        P::Action | P::Repeat | P::Trigger => &["VALARM"],
+        P::LastModified => &["VEVENT", "VTODO", "VJOURNAL", "VTIMEZONE"],
+    }
+}
+
+/// The values allowed for a property with an enumerated value, in the given component.
+pub fn enumerated_values(name: ast::PropertyName, component: &str) -> &'static [&'static str] {
+    use ast::PropertyName as P;
+    match name {
+        P::Begin | P::End => &[
+            "VCALENDAR",
+            "VEVENT",
+            "VTODO",
+            "VJOURNAL",
+            "VFREEBUSY",
+            "VTIMEZONE",
+            "VALARM",
+            "STANDARD",
+            "DAYLIGHT",
+        ],
+        P::CalScale => &["GREGORIAN"],
+        P::Method => &[
+            "PUBLISH",
+            "REQUEST",
+            "REPLY",
+            "ADD",
+            "CANCEL",
+            "REFRESH",
+            "COUNTER",
+            "DECLINECOUNTER",
+        ],
+        P::Version => &["2.0"],
+        P::Class => &["PUBLIC", "PRIVATE", "CONFIDENTIAL"],
+        P::Status => match component.to_uppercase().as_str() {
+            "VEVENT" => &["TENTATIVE", "CONFIRMED", "CANCELLED"],
+            "VTODO" => &["NEEDS-ACTION", "COMPLETED", "IN-PROCESS", "CANCELLED"],
+            "VJOURNAL" => &["DRAFT", "FINAL", "CANCELLED"],
+            _ => &[
+                "TENTATIVE",
+                "CONFIRMED",
+                "CANCELLED",
+                "NEEDS-ACTION",
+                "COMPLETED",
+                "IN-PROCESS",
+                "DRAFT",
+                "FINAL",
+            ],
+        },
+        P::Transp => &["OPAQUE", "TRANSPARENT"],
+        P::Action => &["AUDIO", "DISPLAY", "EMAIL"],
+        _ => &[],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use expect_test::expect;
@@ -0,0 +1,885 @@
+//! Recurrence rules, the value of `RRULE` properties, and expanding them into occurrences.
+
+use std::str::FromStr;
+
+use chrono::{Datelike as _, Days, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike as _};
+
+use crate::ast::{parse_value, Span};
+use crate::value::{short_month, Date, Time, Value, ValueType, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl FromStr for Frequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SECONDLY" => Ok(Self::Secondly),
+            "MINUTELY" => Ok(Self::Minutely),
+            "HOURLY" => Ok(Self::Hourly),
+            "DAILY" => Ok(Self::Daily),
+            "WEEKLY" => Ok(Self::Weekly),
+            "MONTHLY" => Ok(Self::Monthly),
+            "YEARLY" => Ok(Self::Yearly),
+            _ => Err(format!("Unknown frequency {s:?}")),
+        }
+    }
+}
+
+impl Frequency {
+    fn adverb(&self) -> &'static str {
+        match self {
+            Frequency::Secondly => "every second",
+            Frequency::Minutely => "every minute",
+            Frequency::Hourly => "hourly",
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+            Frequency::Yearly => "yearly",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Frequency::Secondly => "seconds",
+            Frequency::Minutely => "minutes",
+            Frequency::Hourly => "hours",
+            Frequency::Daily => "days",
+            Frequency::Weekly => "weeks",
+            Frequency::Monthly => "months",
+            Frequency::Yearly => "years",
+        }
+    }
+}
+
+impl FromStr for Weekday {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "MO" => Ok(Self::Monday),
+            "TU" => Ok(Self::Tuesday),
+            "WE" => Ok(Self::Wednesday),
+            "TH" => Ok(Self::Thursday),
+            "FR" => Ok(Self::Friday),
+            "SA" => Ok(Self::Saturday),
+            "SU" => Ok(Self::Sunday),
+            _ => Err(format!("Unknown weekday {s:?}")),
+        }
+    }
+}
+
+/// A `BYDAY` entry, e.g. `MO` for every Monday or `-1FR` for the last Friday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i8>,
+    pub weekday: Weekday,
+}
+
+impl FromStr for WeekdayNum {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s.len().saturating_sub(2);
+        let (ordinal, weekday) = s.split_at(split);
+        let ordinal = if ordinal.is_empty() {
+            None
+        } else {
+            Some(parse_in_range(ordinal, -53, 53)?)
+        };
+        Ok(Self {
+            ordinal,
+            weekday: weekday.parse()?,
+        })
+    }
+}
+
+impl WeekdayNum {
+    fn describe(&self) -> String {
+        match self.ordinal {
+            None => self.weekday.short().to_owned(),
+            Some(-1) => format!("last {}", self.weekday.short()),
+            Some(n) if n < 0 => format!("{} to last {}", ordinal(-n), self.weekday.short()),
+            Some(n) => format!("{} {}", ordinal(n), self.weekday.short()),
+        }
+    }
+}
+
+fn ordinal(n: i8) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (1, 11) | (2, 12) | (3, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// A parsed `RECUR` value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    /// The last date, or date and time, of the recurrence.
+    pub until: Option<(Date, Option<Time>)>,
+    pub count: Option<u32>,
+    pub interval: u32,
+    pub by_second: Vec<u8>,
+    pub by_minute: Vec<u8>,
+    pub by_hour: Vec<u8>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i8>,
+    pub by_year_day: Vec<i16>,
+    pub by_week_no: Vec<i8>,
+    pub by_month: Vec<u8>,
+    pub by_set_pos: Vec<i16>,
+    pub week_start: Weekday,
+}
+
+fn parse_in_range<T>(s: &str, min: T, max: T) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + Default + std::fmt::Display,
+{
+    let n = s
+        .strip_prefix('+')
+        .unwrap_or(s)
+        .parse::<T>()
+        .map_err(|_| format!("{s:?} is not a number"))?;
+    // ranges that allow counting from the end, like -31 to 31, have no zero
+    if n < min || n > max || n == T::default() && min != T::default() {
+        return Err(format!("{s:?} is not in the range {min} to {max}"));
+    }
+    Ok(n)
+}
+
+fn parse_list<T>(value: &str, min: T, max: T) -> Result<Vec<T>, String>
+where
+    T: FromStr + PartialOrd + Default + std::fmt::Display + Copy,
+{
+    value
+        .split(',')
+        .map(|v| parse_in_range(v, min, max))
+        .collect()
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut frequency = None;
+        let mut rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            until: None,
+            count: None,
+            interval: 1,
+            by_second: Vec::new(),
+            by_minute: Vec::new(),
+            by_hour: Vec::new(),
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_year_day: Vec::new(),
+            by_week_no: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Monday,
+        };
+        let mut seen = Vec::new();
+        for part in s.split(';') {
+            let Some((name, value)) = part.split_once('=') else {
+                return Err(format!("Missing '=' in {part:?}"));
+            };
+            let name = name.to_uppercase();
+            if seen.contains(&name) {
+                return Err(format!("{name} is given more than once"));
+            }
+            seen.push(name.clone());
+            let value = value.to_uppercase();
+            match name.as_str() {
+                "FREQ" => frequency = Some(value.parse()?),
+                "UNTIL" => rule.until = Some(parse_until(&value)?),
+                "COUNT" => rule.count = Some(parse_in_range(&value, 1, u32::MAX)?),
+                "INTERVAL" => rule.interval = parse_in_range(&value, 1, u32::MAX)?,
+                "BYSECOND" => rule.by_second = parse_list(&value, 0, 60)?,
+                "BYMINUTE" => rule.by_minute = parse_list(&value, 0, 59)?,
+                "BYHOUR" => rule.by_hour = parse_list(&value, 0, 23)?,
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(WeekdayNum::from_str)
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => rule.by_month_day = parse_list(&value, -31, 31)?,
+                "BYYEARDAY" => rule.by_year_day = parse_list(&value, -366, 366)?,
+                "BYWEEKNO" => rule.by_week_no = parse_list(&value, -53, 53)?,
+                "BYMONTH" => rule.by_month = parse_list(&value, 1, 12)?,
+                "BYSETPOS" => rule.by_set_pos = parse_list(&value, -366, 366)?,
+                "WKST" => rule.week_start = value.parse()?,
+                _ if name.starts_with("X-") => {}
+                _ => return Err(format!("Unknown rule part {name:?}")),
+            }
+        }
+        let Some(frequency) = frequency else {
+            return Err("Missing FREQ".to_owned());
+        };
+        rule.frequency = frequency;
+        if rule.until.is_some() && rule.count.is_some() {
+            return Err("UNTIL and COUNT cannot both be given".to_owned());
+        }
+        Ok(rule)
+    }
+}
+
+fn parse_until(value: &str) -> Result<(Date, Option<Time>), String> {
+    let span = Span::new(value);
+    if let Ok((rest, Value::DateTime(date, time))) = parse_value(span, ValueType::DateTime) {
+        if rest.is_empty() {
+            return Ok((date, Some(time)));
+        }
+    }
+    match parse_value(span, ValueType::Date) {
+        Ok((_, Value::Date(date))) if value.len() == 8 => Ok((date, None)),
+        _ => Err(format!("UNTIL {value:?} is not a DATE or DATE-TIME")),
+    }
+}
+
+impl RecurrenceRule {
+    /// A human readable description of the rule, e.g. `weekly on Mon, Wed until 1 Mar 2023`.
+    pub fn describe(&self) -> String {
+        let mut description = if self.interval == 1 {
+            self.frequency.adverb().to_owned()
+        } else {
+            format!("every {} {}", self.interval, self.frequency.unit())
+        };
+        if !self.by_month.is_empty() {
+            let months = self
+                .by_month
+                .iter()
+                .map(|m| short_month(*m))
+                .collect::<Vec<_>>();
+            description.push_str(&format!(" in {}", months.join(", ")));
+        }
+        if !self.by_day.is_empty() {
+            let days = self
+                .by_day
+                .iter()
+                .map(WeekdayNum::describe)
+                .collect::<Vec<_>>();
+            description.push_str(&format!(" on {}", days.join(", ")));
+        }
+        if !self.by_month_day.is_empty() {
+            let days = self
+                .by_month_day
+                .iter()
+                .map(|d| match d {
+                    -1 => "the last day".to_owned(),
+                    d if *d < 0 => format!("the {} to last day", ordinal(-d)),
+                    d => format!("the {}", ordinal(*d)),
+                })
+                .collect::<Vec<_>>();
+            description.push_str(&format!(" on {}", days.join(", ")));
+        }
+        if !self.by_hour.is_empty() {
+            let minute = self.by_minute.first().copied().unwrap_or(0);
+            let times = self
+                .by_hour
+                .iter()
+                .map(|h| format!("{h:02}:{minute:02}"))
+                .collect::<Vec<_>>();
+            description.push_str(&format!(" at {}", times.join(", ")));
+        }
+        if let Some(count) = self.count {
+            let times = if count == 1 { "time" } else { "times" };
+            description.push_str(&format!(", {count} {times}"));
+        }
+        if let Some((date, time)) = &self.until {
+            description.push_str(&format!(
+                " until {} {} {}",
+                date.day,
+                short_month(date.month),
+                date.year
+            ));
+            if let Some(time) = time.filter(|t| (t.hour, t.minute, t.second) != (0, 0, 0)) {
+                description.push_str(&format!(" {}", time.short()));
+            }
+        }
+        description
+    }
+}
+
+impl RecurrenceRule {
+    /// The starts of the occurrences of the rule from `from` until `to`, in the local time of
+    /// `start`, which is always the first occurrence. A UTC `UNTIL` is converted to the time zone
+    /// `tz` of `start`, or taken as local if there is none.
+    ///
+    /// The occurrences are found lazily, a period at a time, and without a `COUNT` the periods
+    /// before `from` are skipped over entirely.
+    pub fn occurrences(
+        &self,
+        start: NaiveDateTime,
+        tz: Option<chrono_tz::Tz>,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Occurrences<'_> {
+        let period = if self.count.is_none() {
+            self.period_index(start, from)
+        } else {
+            0
+        };
+        Occurrences {
+            rule: self,
+            start,
+            until: self.until.map(|(date, time)| until_local(date, time, tz)),
+            from,
+            to,
+            period,
+            pending: Vec::new().into_iter(),
+            count: 0,
+            finished: false,
+        }
+    }
+
+    /// The index of the period that `time` falls in, counted in intervals from `start`.
+    fn period_index(&self, start: NaiveDateTime, time: NaiveDateTime) -> u64 {
+        let (start_date, date) = (start.date(), time.date());
+        let week = |date: NaiveDate| date - Days::new(days_from(self.week_start, date));
+        let units = match self.frequency {
+            Frequency::Yearly => i64::from(date.year() - start_date.year()),
+            Frequency::Monthly => {
+                i64::from(date.year() - start_date.year()) * 12 + i64::from(date.month())
+                    - i64::from(start_date.month())
+            }
+            Frequency::Weekly => (week(date) - week(start_date)).num_weeks(),
+            Frequency::Daily => (date - start_date).num_days(),
+            Frequency::Hourly => {
+                (time - start.with_minute(0).unwrap().with_second(0).unwrap()).num_hours()
+            }
+            Frequency::Minutely => (time - start.with_second(0).unwrap()).num_minutes(),
+            Frequency::Secondly => (time - start).num_seconds(),
+        };
+        u64::try_from(units).unwrap_or(0) / u64::from(self.interval)
+    }
+
+    /// The first moment of the `n`th period of the rule, counted in intervals from `start`.
+    fn period_start(&self, start: NaiveDateTime, n: u64) -> Option<NaiveDateTime> {
+        let steps = n.checked_mul(self.interval.into())?;
+        let date = start.date();
+        let midnight = NaiveTime::MIN;
+        let period_start = match self.frequency {
+            Frequency::Yearly => {
+                NaiveDate::from_ymd_opt(date.year() + i32::try_from(steps).ok()?, 1, 1)?
+                    .and_time(midnight)
+            }
+            Frequency::Monthly => date
+                .with_day(1)?
+                .checked_add_months(Months::new(u32::try_from(steps).ok()?))?
+                .and_time(midnight),
+            Frequency::Weekly => {
+                let offset = days_from(self.week_start, date);
+                (date - Days::new(offset))
+                    .checked_add_days(Days::new(steps.checked_mul(7)?))?
+                    .and_time(midnight)
+            }
+            Frequency::Daily => date.checked_add_days(Days::new(steps))?.and_time(midnight),
+            Frequency::Hourly => start
+                .with_minute(0)?
+                .with_second(0)?
+                .checked_add_signed(chrono::Duration::try_hours(steps.try_into().ok()?)?)?,
+            Frequency::Minutely => start
+                .with_second(0)?
+                .checked_add_signed(chrono::Duration::try_minutes(steps.try_into().ok()?)?)?,
+            Frequency::Secondly => {
+                start.checked_add_signed(chrono::Duration::try_seconds(steps.try_into().ok()?)?)?
+            }
+        };
+        // the dates a recurrence can be given for
+        (period_start.year() <= 9999).then_some(period_start)
+    }
+
+    /// The occurrences in the period beginning at `period_start`, in order.
+    fn period_occurrences(
+        &self,
+        start: NaiveDateTime,
+        period_start: NaiveDateTime,
+    ) -> Vec<NaiveDateTime> {
+        let day = period_start.date();
+        let dates: Vec<NaiveDate> = match self.frequency {
+            Frequency::Yearly => {
+                let first = day.with_ordinal(1).unwrap();
+                first
+                    .iter_days()
+                    .take_while(|d| d.year() == day.year())
+                    .collect()
+            }
+            Frequency::Monthly => day
+                .iter_days()
+                .take_while(|d| d.month() == day.month())
+                .collect(),
+            Frequency::Weekly => day.iter_days().take(7).collect(),
+            _ => vec![day],
+        };
+        let dates = dates
+            .into_iter()
+            .filter(|date| self.date_matches(start.date(), *date))
+            .collect::<Vec<_>>();
+        if dates.is_empty() {
+            return Vec::new();
+        }
+
+        // the parts of the time that are finer than the frequency are expanded, the others limit
+        let expand = |by: &[u8], coarser: bool, from_start: u32, from_period: u32| {
+            if coarser {
+                if by.is_empty() {
+                    vec![from_start]
+                } else {
+                    by.iter().map(|&v| u32::from(v)).collect()
+                }
+            } else if by.is_empty() || by.contains(&(from_period as u8)) {
+                vec![from_period]
+            } else {
+                Vec::new()
+            }
+        };
+        let hours = expand(
+            &self.by_hour,
+            self.frequency > Frequency::Hourly,
+            start.hour(),
+            period_start.hour(),
+        );
+        let minutes = expand(
+            &self.by_minute,
+            self.frequency > Frequency::Minutely,
+            start.minute(),
+            period_start.minute(),
+        );
+        let seconds = expand(
+            &self.by_second,
+            self.frequency > Frequency::Secondly,
+            start.second(),
+            period_start.second(),
+        );
+
+        let mut occurrences = Vec::new();
+        for date in dates {
+            for hour in &hours {
+                for minute in &minutes {
+                    for second in &seconds {
+                        if let Some(time) = NaiveTime::from_hms_opt(*hour, *minute, *second) {
+                            occurrences.push(date.and_time(time));
+                        }
+                    }
+                }
+            }
+        }
+        occurrences.sort();
+        occurrences.dedup();
+
+        if !self.by_set_pos.is_empty() {
+            let len = occurrences.len();
+            let mut selected = self
+                .by_set_pos
+                .iter()
+                .filter_map(|&pos| {
+                    let offset = usize::from(pos.unsigned_abs());
+                    let index = if pos > 0 {
+                        offset - 1
+                    } else {
+                        len.checked_sub(offset)?
+                    };
+                    occurrences.get(index).copied()
+                })
+                .collect::<Vec<_>>();
+            selected.sort();
+            selected.dedup();
+            occurrences = selected;
+        }
+        occurrences
+    }
+
+    /// Whether the rule gives occurrences on `date`, for a recurrence starting on `start`.
+    fn date_matches(&self, start: NaiveDate, date: NaiveDate) -> bool {
+        let in_list = |list: &[i16], n: u32, len: u32| {
+            list.is_empty()
+                || list.iter().any(|&v| {
+                    i64::from(v) == i64::from(n)
+                        || i64::from(v) == i64::from(n) - i64::from(len) - 1
+                })
+        };
+        let days_in_month = days_in_month(date);
+        let days_in_year = date.with_ordinal(366).map_or(365, |_| 366);
+        let by_month_day = self
+            .by_month_day
+            .iter()
+            .map(|&d| d.into())
+            .collect::<Vec<i16>>();
+        let by_week_no = self
+            .by_week_no
+            .iter()
+            .map(|&w| w.into())
+            .collect::<Vec<i16>>();
+
+        if !self.by_month.is_empty() && !self.by_month.contains(&(date.month() as u8)) {
+            return false;
+        }
+        if self.frequency == Frequency::Yearly && !by_week_no.is_empty() {
+            let (week, weeks) = week_number(date, self.week_start);
+            if !in_list(&by_week_no, week, weeks) {
+                return false;
+            }
+        }
+        if !in_list(&self.by_year_day, date.ordinal(), days_in_year) {
+            return false;
+        }
+        if !in_list(&by_month_day, date.day(), days_in_month) {
+            return false;
+        }
+        if !self.by_day.is_empty() && !self.by_day.iter().any(|d| self.weekday_matches(d, date)) {
+            return false;
+        }
+
+        // without any of the finer parts, occurrences fall on the same day as the start
+        let no_days =
+            self.by_year_day.is_empty() && by_month_day.is_empty() && self.by_day.is_empty();
+        match self.frequency {
+            Frequency::Yearly if no_days && by_week_no.is_empty() => {
+                (!self.by_month.is_empty() || date.month() == start.month())
+                    && date.day() == start.day()
+            }
+            Frequency::Yearly if no_days => date.weekday() == start.weekday(),
+            Frequency::Monthly if no_days => date.day() == start.day(),
+            Frequency::Weekly if self.by_day.is_empty() => date.weekday() == start.weekday(),
+            _ => true,
+        }
+    }
+
+    fn weekday_matches(&self, day: &WeekdayNum, date: NaiveDate) -> bool {
+        if Weekday::from(date.weekday()) != day.weekday {
+            return false;
+        }
+        let Some(ordinal) = day.ordinal else {
+            return true;
+        };
+        // the nth weekday counts within the month or year of the period
+        let (index, count) = match self.frequency {
+            Frequency::Yearly if self.by_month.is_empty() => {
+                let days_in_year = date.with_ordinal(366).map_or(365, |_| 366);
+                (
+                    (date.ordinal() - 1) / 7,
+                    (days_in_year - date.ordinal()) / 7,
+                )
+            }
+            Frequency::Yearly | Frequency::Monthly => {
+                ((date.day() - 1) / 7, (days_in_month(date) - date.day()) / 7)
+            }
+            _ => return true,
+        };
+        if ordinal > 0 {
+            index + 1 == ordinal as u32
+        } else {
+            count + 1 == ordinal.unsigned_abs() as u32
+        }
+    }
+}
+
+/// The occurrences of a [`RecurrenceRule`], see [`RecurrenceRule::occurrences`].
+pub struct Occurrences<'a> {
+    rule: &'a RecurrenceRule,
+    start: NaiveDateTime,
+    until: Option<NaiveDateTime>,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    /// The next period to expand.
+    period: u64,
+    /// The rest of the occurrences of the last period expanded.
+    pending: std::vec::IntoIter<NaiveDateTime>,
+    /// How many occurrences there have been so far, including those before `from`.
+    count: u32,
+    finished: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        let rule = self.rule;
+        if self.count == 0 {
+            self.count = 1;
+            if self.start > self.to {
+                self.finished = true;
+            } else if self.start >= self.from {
+                return Some(self.start);
+            }
+        }
+        while !self.finished {
+            if let Some(occurrence) = self.pending.next() {
+                if occurrence <= self.start {
+                    continue;
+                }
+                if occurrence > self.to
+                    || self.until.is_some_and(|until| occurrence > until)
+                    || rule.count.is_some_and(|count| self.count >= count)
+                {
+                    self.finished = true;
+                    break;
+                }
+                self.count += 1;
+                if occurrence >= self.from {
+                    return Some(occurrence);
+                }
+                continue;
+            }
+
+            let Some(period_start) = rule.period_start(self.start, self.period) else {
+                self.finished = true;
+                break;
+            };
+            if period_start > self.to || self.until.is_some_and(|until| period_start > until) {
+                self.finished = true;
+                break;
+            }
+            // periods shorter than a day on days the rule doesn't match can all be skipped
+            if rule.frequency < Frequency::Daily
+                && !rule.date_matches(self.start.date(), period_start.date())
+            {
+                let date = period_start.date();
+                // straight to the next month if the rule leaves out the whole month
+                let next =
+                    if rule.by_month.is_empty() || rule.by_month.contains(&(date.month() as u8)) {
+                        date.succ_opt()
+                    } else {
+                        date.with_day(1)
+                            .and_then(|d| d.checked_add_months(Months::new(1)))
+                    };
+                let Some(next) = next else {
+                    self.finished = true;
+                    break;
+                };
+                let next = rule.period_index(self.start, next.and_time(NaiveTime::MIN));
+                self.period = next.max(self.period + 1);
+                continue;
+            }
+            self.period += 1;
+            self.pending = rule
+                .period_occurrences(self.start, period_start)
+                .into_iter();
+        }
+        None
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).unwrap();
+    let next = first + Months::new(1);
+    (next - first).num_days() as u32
+}
+
+/// Days from the last `weekday` on or before `date`.
+fn days_from(weekday: Weekday, date: NaiveDate) -> u64 {
+    let from = weekday as u64;
+    let day = u64::from(date.weekday().num_days_from_monday());
+    (day + 7 - from) % 7
+}
+
+/// The first day of week 1 of a year, the first week with at least 4 days in the year.
+fn first_week_start(year: i32, week_start: Weekday) -> NaiveDate {
+    let first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+    let offset = days_from(week_start, first);
+    if offset <= 3 {
+        first - Days::new(offset)
+    } else {
+        first + Days::new(7 - offset)
+    }
+}
+
+/// The week number of a date, and the number of weeks in the year it is counted in.
+fn week_number(date: NaiveDate, week_start: Weekday) -> (u32, u32) {
+    let mut year = date.year();
+    if date < first_week_start(year, week_start) {
+        year -= 1;
+    } else if date >= first_week_start(year + 1, week_start) {
+        year += 1;
+    }
+    let first = first_week_start(year, week_start);
+    let weeks = (first_week_start(year + 1, week_start) - first).num_weeks() as u32;
+    ((date - first).num_weeks() as u32 + 1, weeks)
+}
+
+/// The `UNTIL` of a rule in local time, the end of the day for dates.
+fn until_local(date: Date, time: Option<Time>, tz: Option<chrono_tz::Tz>) -> NaiveDateTime {
+    let day = date.to_naive().unwrap_or(NaiveDate::MAX);
+    let Some(time) = time else {
+        return day.and_hms_opt(23, 59, 59).unwrap();
+    };
+    let naive = day
+        .and_hms_opt(time.hour.into(), time.minute.into(), time.second.into())
+        .unwrap_or(day.and_time(NaiveTime::MIN));
+    match tz {
+        Some(tz) if time.utc => naive.and_utc().with_timezone(&tz).naive_local(),
+        _ => naive,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    #[test]
+    fn describe_rules() {
+        let describe = |s: &str| {
+            s.parse::<RecurrenceRule>()
+                .map(|r| r.describe())
+                .unwrap_or_else(|e| format!("error: {e}"))
+        };
+        let rules = [
+            "FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20230301T000000Z",
+            "FREQ=DAILY;UNTIL=20230301T120000",
+            "FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR;COUNT=10",
+            "FREQ=YEARLY;BYMONTH=3;BYMONTHDAY=1",
+            "FREQ=DAILY;BYHOUR=9,17",
+            "BYDAY=MO",
+            "FREQ=DAILY;COUNT=2;UNTIL=20230301",
+            "FREQ=DAILY;BYMONTH=13",
+        ]
+        .map(describe);
+        expect![[r#"
+            [
+                "weekly on Mon, Wed until 1 Mar 2023",
+                "daily until 1 Mar 2023 12:00",
+                "every 2 months on last Fri, 10 times",
+                "yearly in Mar on the 1st",
+                "daily at 09:00, 17:00",
+                "error: Missing FREQ",
+                "error: UNTIL and COUNT cannot both be given",
+                "error: \"13\" is not in the range 1 to 12",
+            ]
+        "#]]
+        .assert_debug_eq(&rules);
+    }
+
+    #[test]
+    fn expand_rules() {
+        let start = |s: &str| NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").unwrap();
+        let expand = |rule: &str, from: &str| {
+            rule.parse::<RecurrenceRule>()
+                .unwrap()
+                .occurrences(start(from), None, start(from), start("20241231T000000"))
+                .map(|o| o.format("%a %Y-%m-%d %H:%M").to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let rules = [
+            ("FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4", "20230306T090000"),
+            ("FREQ=MONTHLY;BYDAY=-1FR;COUNT=3", "20230131T170000"),
+            ("FREQ=MONTHLY;BYMONTHDAY=31;COUNT=3", "20230131T170000"),
+            ("FREQ=YEARLY;UNTIL=20240301", "20200229T000000"),
+            (
+                "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;COUNT=2",
+                "20200229T120000",
+            ),
+            (
+                "FREQ=DAILY;INTERVAL=2;BYHOUR=9,17;COUNT=3",
+                "20230301T090000",
+            ),
+            (
+                "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;COUNT=3",
+                "20230131T120000",
+            ),
+            (
+                "FREQ=HOURLY;INTERVAL=8;UNTIL=20230302T000000",
+                "20230301T000000",
+            ),
+            ("FREQ=YEARLY;BYWEEKNO=1;BYDAY=MO;COUNT=3", "20230102T080000"),
+        ]
+        .map(|(rule, from)| format!("{rule}: {}", expand(rule, from)));
+        expect![[r#"
+            [
+                "FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4: Mon 2023-03-06 09:00, Wed 2023-03-08 09:00, Mon 2023-03-13 09:00, Wed 2023-03-15 09:00",
+                "FREQ=MONTHLY;BYDAY=-1FR;COUNT=3: Tue 2023-01-31 17:00, Fri 2023-02-24 17:00, Fri 2023-03-31 17:00",
+                "FREQ=MONTHLY;BYMONTHDAY=31;COUNT=3: Tue 2023-01-31 17:00, Fri 2023-03-31 17:00, Wed 2023-05-31 17:00",
+                "FREQ=YEARLY;UNTIL=20240301: Sat 2020-02-29 00:00, Thu 2024-02-29 00:00",
+                "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;COUNT=2: Sat 2020-02-29 12:00, Thu 2024-02-29 12:00",
+                "FREQ=DAILY;INTERVAL=2;BYHOUR=9,17;COUNT=3: Wed 2023-03-01 09:00, Wed 2023-03-01 17:00, Fri 2023-03-03 09:00",
+                "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;COUNT=3: Tue 2023-01-31 12:00, Tue 2023-02-28 12:00, Fri 2023-03-31 12:00",
+                "FREQ=HOURLY;INTERVAL=8;UNTIL=20230302T000000: Wed 2023-03-01 00:00, Wed 2023-03-01 08:00, Wed 2023-03-01 16:00, Thu 2023-03-02 00:00",
+                "FREQ=YEARLY;BYWEEKNO=1;BYDAY=MO;COUNT=3: Mon 2023-01-02 08:00, Mon 2024-01-01 08:00, Mon 2024-12-30 08:00",
+            ]
+        "#]]
+        .assert_debug_eq(&rules);
+
+        // more occurrences in a period than fit in the positions
+        let list = |n: u8| (0..n).map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+        let rule = format!(
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR,SA,SU;BYHOUR={};BYMINUTE={};BYSETPOS=-1,1;COUNT=3",
+            list(24),
+            list(60)
+        );
+        expect!["Sun 2023-01-01 00:00, Tue 2023-01-31 23:59, Wed 2023-02-01 00:00"]
+            .assert_eq(&expand(&rule, "20230101T000000"));
+    }
+
+    #[test]
+    fn expand_window() {
+        let time = |s: &str| NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S").unwrap();
+        let window = |rule: &str, start: &str, from: &str, to: &str| {
+            rule.parse::<RecurrenceRule>()
+                .unwrap()
+                .occurrences(time(start), None, time(from), time(to))
+                .map(|o| o.format("%Y-%m-%d %H:%M:%S").to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let windows = [
+            (
+                "FREQ=SECONDLY",
+                "20000101T000000",
+                "20200101T000000",
+                "20200101T000002",
+            ),
+            (
+                "FREQ=MINUTELY;INTERVAL=7;BYMONTH=2",
+                "20000101T000000",
+                "20200215T120000",
+                "20200215T122000",
+            ),
+            (
+                "FREQ=SECONDLY;BYMONTHDAY=31;BYMONTH=2",
+                "20000101T000000",
+                "20200101T000000",
+                "20200101T000000",
+            ),
+            (
+                "FREQ=DAILY;BYMONTH=2;BYMONTHDAY=30",
+                "20000101T000000",
+                "20230101T000000",
+                "20240101T000000",
+            ),
+            (
+                "FREQ=DAILY;COUNT=10",
+                "20230101T090000",
+                "20230108T000000",
+                "20240101T000000",
+            ),
+        ]
+        .map(|(rule, start, from, to)| format!("{rule}: {}", window(rule, start, from, to)));
+        expect![[r#"
+            [
+                "FREQ=SECONDLY: 2020-01-01 00:00:00, 2020-01-01 00:00:01, 2020-01-01 00:00:02",
+                "FREQ=MINUTELY;INTERVAL=7;BYMONTH=2: 2020-02-15 12:01:00, 2020-02-15 12:08:00, 2020-02-15 12:15:00",
+                "FREQ=SECONDLY;BYMONTHDAY=31;BYMONTH=2: ",
+                "FREQ=DAILY;BYMONTH=2;BYMONTHDAY=30: ",
+                "FREQ=DAILY;COUNT=10: 2023-01-08 09:00:00, 2023-01-09 09:00:00, 2023-01-10 09:00:00",
+            ]
+        "#]]
+        .assert_debug_eq(&windows);
+    }
+}
@@ -0,0 +1,269 @@
+//! Identifiers that link parts of a calendar together: time zone IDs used by `TZID` parameters,
+//! component UIDs used by `RELATED-TO` and recurrence overrides, and the names on matching
+//! `BEGIN` and `END` lines.
+
+use lsp_types::{Position, Range};
+use nom::InputTake as _;
+
+use crate::ast::{Document, ParameterName, PropertyName, Span};
+use crate::diagnostics::{property_range, span_range};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol {
+    Timezone,
+    Uid,
+}
+
+#[derive(Debug)]
+pub struct Occurrence<'a> {
+    pub symbol: Symbol,
+    pub span: Span<'a>,
+    /// Whether this is where the identifier is defined, rather than referred to.
+    pub definition: bool,
+}
+
+impl<'a> Occurrence<'a> {
+    pub fn name(&self) -> &'a str {
+        self.span.fragment()
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        let range = span_range(&self.span);
+        range.start <= position && position <= range.end
+    }
+}
+
+/// Strip surrounding quotes from a parameter value.
+pub(crate) fn unquote(span: Span) -> Span {
+    let fragment = span.fragment();
+    if fragment.len() >= 2 && fragment.starts_with('"') && fragment.ends_with('"') {
+        let (rest, _) = span.take_split(1);
+        rest.take_split(fragment.len() - 2).1
+    } else {
+        span
+    }
+}
+
+/// Find all of the time zone and UID identifiers in the document.
+pub fn occurrences<'a>(document: &Document<'a>) -> Vec<Occurrence<'a>> {
+    let mut occurrences = Vec::new();
+    for component in document.all_components() {
+        let overrides = component
+            .property(document, PropertyName::RecurrenceId)
+            .is_some();
+        for property in component.properties(document) {
+            let Some(value) = property.value else {
+                continue;
+            };
+            match property.name {
+                Some(PropertyName::TzId) if component.is("VTIMEZONE") => {
+                    occurrences.push(Occurrence {
+                        symbol: Symbol::Timezone,
+                        span: value,
+                        definition: true,
+                    })
+                }
+                Some(PropertyName::Uid) => occurrences.push(Occurrence {
+                    symbol: Symbol::Uid,
+                    span: value,
+                    definition: !overrides,
+                }),
+                Some(PropertyName::RelatedTo) => occurrences.push(Occurrence {
+                    symbol: Symbol::Uid,
+                    span: value,
+                    definition: false,
+                }),
+                _ => {}
+            }
+            for param in &property.params {
+                if let (Some(ParameterName::TZId), Some(value)) = (param.name, param.value) {
+                    occurrences.push(Occurrence {
+                        symbol: Symbol::Timezone,
+                        span: unquote(value),
+                        definition: false,
+                    });
+                }
+            }
+        }
+    }
+    occurrences
+}
+
+/// Find the identifier under the cursor, treating a `RECURRENCE-ID` as a reference to the UID of
+/// the recurring component it overrides.
+pub fn occurrence_at<'a>(document: &Document<'a>, position: Position) -> Option<Occurrence<'a>> {
+    let mut occurrences = occurrences(document);
+    if let Some(i) = occurrences.iter().position(|o| o.contains(position)) {
+        return Some(occurrences.swap_remove(i));
+    }
+    let component = document.all_components().into_iter().find(|c| {
+        c.property(document, PropertyName::RecurrenceId)
+            .is_some_and(|p| {
+                let range = property_range(p);
+                range.start <= position && position <= range.end
+            })
+    })?;
+    let uid = component.property(document, PropertyName::Uid)?.value?;
+    Some(Occurrence {
+        symbol: Symbol::Uid,
+        span: uid,
+        definition: false,
+    })
+}
+
+/// The ranges of the component names on the `BEGIN` and `END` lines of the component whose name
+/// is under the cursor.
+pub fn component_name_ranges(document: &Document, position: Position) -> Option<[Range; 2]> {
+    document.all_components().into_iter().find_map(|component| {
+        let begin = document.properties[component.begin].value?;
+        let end = document.properties[component.end?].value?;
+        let ranges = [span_range(&begin), span_range(&end)];
+        ranges
+            .iter()
+            .any(|r| r.start <= position && position <= r.end)
+            .then_some(ranges)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::ast::parse_document;
+
+    #[test]
+    fn find_occurrences() {
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR
+BEGIN:VTIMEZONE
+TZID:Europe/London
+END:VTIMEZONE
+BEGIN:VEVENT
+UID:master@example.com
+DTSTART;TZID=\"Europe/London\":20221008T170000
+END:VEVENT
+BEGIN:VEVENT
+UID:master@example.com
+RECURRENCE-ID;TZID=Europe/London:20221015T170000
+RELATED-TO:other@example.com
+END:VEVENT
+END:VCALENDAR
+",
+        ));
+        let found = occurrences(&document)
+            .iter()
+            .map(|o| (o.symbol, o.name(), o.span.location_line(), o.definition))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    Timezone,
+                    "Europe/London",
+                    3,
+                    true,
+                ),
+                (
+                    Uid,
+                    "master@example.com",
+                    6,
+                    true,
+                ),
+                (
+                    Timezone,
+                    "Europe/London",
+                    7,
+                    false,
+                ),
+                (
+                    Uid,
+                    "master@example.com",
+                    10,
+                    false,
+                ),
+                (
+                    Timezone,
+                    "Europe/London",
+                    11,
+                    false,
+                ),
+                (
+                    Uid,
+                    "other@example.com",
+                    12,
+                    false,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&found);
+        let at = occurrence_at(
+            &document,
+            Position {
+                line: 10,
+                character: 3,
+            },
+        )
+        .map(|o| (o.symbol, o.name(), o.span.location_line()));
+        expect![[r#"
+            Some(
+                (
+                    Uid,
+                    "master@example.com",
+                    10,
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&at);
+    }
+
+    #[test]
+    fn linked_component_names() {
+        let document = parse_document(Span::new(
+            "BEGIN:VCALENDAR
+BEGIN:VEVENT
+BEGIN:VALARM
+END:VALARM
+END:VEVENT
+END:VCALENDAR
+",
+        ));
+        let ranges = |line, character| {
+            component_name_ranges(&document, Position { line, character })
+                .map(|rs| rs.map(|r| (r.start.line, r.start.character, r.end.character)))
+        };
+        expect![[r#"
+            [
+                Some(
+                    [
+                        (
+                            1,
+                            6,
+                            12,
+                        ),
+                        (
+                            4,
+                            4,
+                            10,
+                        ),
+                    ],
+                ),
+                Some(
+                    [
+                        (
+                            2,
+                            6,
+                            12,
+                        ),
+                        (
+                            3,
+                            4,
+                            10,
+                        ),
+                    ],
+                ),
+                None,
+            ]
+        "#]]
+        .assert_debug_eq(&[ranges(1, 8), ranges(3, 10), ranges(0, 2)]);
+    }
+}
@@ -0,0 +1,290 @@
+//! Rendering diagnostics for the command line, for people or as JSON lines and SARIF for other
+//! tools.
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use serde_json::{json, Value};
+use strum::IntoEnumIterator as _;
+
+use crate::diagnostics::Fix;
+use crate::lines::physical_lines;
+use crate::rules::Rule;
+
+pub fn severity_label(severity: Option<DiagnosticSeverity>) -> &'static str {
+    match severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) | None => "warning",
+        Some(DiagnosticSeverity::INFORMATION) => "info",
+        _ => "hint",
+    }
+}
+
+fn location(path: &str, position: Position) -> String {
+    format!("{path}:{}:{}", position.line + 1, position.character + 1)
+}
+
+/// Render a diagnostic like a compiler message, with an excerpt of the line it is on, e.g.
+///
+/// ```text
+/// warning[ICAL001]: Unknown property "FOO"
+///  --> calendar.ics:3:1
+///   |
+/// 3 | FOO:bar
+///   | ^^^
+/// ```
+pub fn render(path: &str, content: &str, diagnostic: &Diagnostic) -> String {
+    let range = diagnostic.range;
+    let code = match Rule::of(diagnostic) {
+        Some(rule) => format!("[{}]", rule.id()),
+        None => String::new(),
+    };
+    let mut out = format!(
+        "{}{code}: {}\n",
+        severity_label(diagnostic.severity),
+        diagnostic.message.replace("\n\n", ": ")
+    );
+    let number = (range.start.line + 1).to_string();
+    let gutter = " ".repeat(number.len());
+    out.push_str(&format!("{gutter}--> {}\n", location(path, range.start)));
+    if let Some(line) = physical_lines(content).nth(range.start.line as usize) {
+        let start = range.start.character as usize;
+        let length = line.content.chars().count();
+        let end = if range.end.line == range.start.line {
+            range.end.character as usize
+        } else {
+            length
+        };
+        // point at the end of the line for empty ranges
+        let carets = end.saturating_sub(start).max(1);
+        out.push_str(&format!("{gutter} |\n"));
+        out.push_str(&format!("{number} | {}\n", line.content));
+        out.push_str(&format!(
+            "{gutter} | {}{}\n",
+            " ".repeat(start.min(length)),
+            "^".repeat(carets)
+        ));
+    }
+    for related in diagnostic.related_information.iter().flatten() {
+        out.push_str(&format!(
+            "{gutter} = note: {} at {}\n",
+            related.message,
+            location(path, related.location.range.start)
+        ));
+    }
+    out
+}
+
+fn fixes(diagnostic: &Diagnostic) -> Vec<Fix> {
+    diagnostic
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value(data).ok())
+        .into_iter()
+        .collect()
+}
+
+/// A range with 1-based lines and columns, in characters.
+fn json_range(range: Range) -> Value {
+    json!({
+        "start": { "line": range.start.line + 1, "column": range.start.character + 1 },
+        "end": { "line": range.end.line + 1, "column": range.end.character + 1 },
+    })
+}
+
+/// A diagnostic as a JSON object, to be written on a line of its own.
+pub fn json_line(path: &str, diagnostic: &Diagnostic) -> Value {
+    let rule = Rule::of(diagnostic);
+    json!({
+        "path": path,
+        "rule": rule.map(Rule::id),
+        "rule_name": rule.map(Rule::name),
+        "severity": severity_label(diagnostic.severity),
+        "message": diagnostic.message,
+        "range": json_range(diagnostic.range),
+        "fixes": fixes(diagnostic).iter().map(|fix| json!({
+            "title": fix.title,
+            "edits": fix.edits.iter().map(|edit| json!({
+                "range": json_range(edit.range),
+                "text": edit.new_text,
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn sarif_level(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::ERROR => "error",
+        DiagnosticSeverity::WARNING => "warning",
+        _ => "note",
+    }
+}
+
+fn sarif_region(range: Range) -> Value {
+    json!({
+        "startLine": range.start.line + 1,
+        "startColumn": range.start.character + 1,
+        "endLine": range.end.line + 1,
+        "endColumn": range.end.character + 1,
+    })
+}
+
+fn sarif_location(path: &str, range: Range) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": path },
+            "region": sarif_region(range),
+        }
+    })
+}
+
+/// A SARIF 2.1.0 log of the diagnostics for each file.
+pub fn sarif(files: &[(String, Vec<Diagnostic>)]) -> Value {
+    let rules = Rule::iter().collect::<Vec<_>>();
+    let results = files
+        .iter()
+        .flat_map(|(path, diagnostics)| diagnostics.iter().map(move |d| (path, d)))
+        .map(|(path, diagnostic)| {
+            let rule = Rule::of(diagnostic);
+            let mut result = json!({
+                "level": sarif_level(diagnostic.severity.unwrap_or(DiagnosticSeverity::WARNING)),
+                "message": { "text": diagnostic.message },
+                "locations": [sarif_location(path, diagnostic.range)],
+            });
+            if let Some(rule) = rule {
+                result["ruleId"] = json!(rule.id());
+                result["ruleIndex"] = json!(rules.iter().position(|r| *r == rule));
+            }
+            if let Some(related) = &diagnostic.related_information {
+                result["relatedLocations"] = related
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r)| {
+                        let mut location = sarif_location(path, r.location.range);
+                        location["id"] = json!(i);
+                        location["message"] = json!({ "text": r.message });
+                        location
+                    })
+                    .collect();
+            }
+            let fixes = fixes(diagnostic);
+            if !fixes.is_empty() {
+                result["fixes"] = fixes
+                    .iter()
+                    .map(|fix| {
+                        json!({
+                            "description": { "text": fix.title },
+                            "artifactChanges": [{
+                                "artifactLocation": { "uri": path },
+                                "replacements": fix.edits.iter().map(|edit| json!({
+                                    "deletedRegion": sarif_region(edit.range),
+                                    "insertedContent": { "text": edit.new_text },
+                                })).collect::<Vec<_>>(),
+                            }],
+                        })
+                    })
+                    .collect();
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "icalls",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/jeffa5/icalls",
+                    "rules": rules.iter().map(|rule| json!({
+                        "id": rule.id(),
+                        "name": rule.name(),
+                        "shortDescription": { "text": rule.description() },
+                        "helpUri": rule.documentation(),
+                        "defaultConfiguration": { "level": sarif_level(rule.default_severity()) },
+                    })).collect::<Vec<_>>(),
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use lsp_types::Url;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::diagnostics::document_diagnostics;
+
+    #[test]
+    fn render_diagnostics() {
+        let content = "BEGIN:VEVENT\r\nDTSTART:20221008T170000Z\r\nDTEND:20221008T160000Z\r\nFOO:bar\r\nEND:VEVENT\r\n";
+        let uri = Url::parse("file:///calendar.ics").unwrap();
+        let rendered = document_diagnostics(Some(&uri), content, &Config::default())
+            .iter()
+            .map(|d| render("calendar.ics", content, d))
+            .collect::<String>();
+        expect![[r#"
+            error[ICAL009]: DTEND must be later than DTSTART
+             --> calendar.ics:3:7
+              |
+            3 | DTEND:20221008T160000Z
+              |       ^^^^^^^^^^^^^^^^
+              = note: DTSTART given here at calendar.ics:2:1
+              = note: DTEND given here at calendar.ics:3:1
+            warning[ICAL001]: Unknown property "FOO"
+             --> calendar.ics:4:1
+              |
+            4 | FOO:bar
+              | ^^^
+        "#]]
+        .assert_eq(&rendered);
+    }
+
+    #[test]
+    fn machine_readable() {
+        let content = "BEGIN:VEVENT\nFOO:bar\nEND:VEVENT\n";
+        let diagnostics = document_diagnostics(None, content, &Config::default());
+        let lines = diagnostics
+            .iter()
+            .map(|d| json_line("calendar.ics", d).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        expect![[r#"
+            {"fixes":[{"edits":[{"range":{"end":{"column":13,"line":1},"start":{"column":13,"line":1}},"text":"\r"},{"range":{"end":{"column":8,"line":2},"start":{"column":8,"line":2}},"text":"\r"},{"range":{"end":{"column":11,"line":3},"start":{"column":11,"line":3}},"text":"\r"}],"title":"Convert line endings to CRLF"}],"message":"Lines end with LF, CRLF line endings are required","path":"calendar.ics","range":{"end":{"column":1,"line":2},"start":{"column":13,"line":1}},"rule":"ICAL013","rule_name":"lf-line-endings","severity":"warning"}
+            {"fixes":[],"message":"Unknown property \"FOO\"","path":"calendar.ics","range":{"end":{"column":4,"line":2},"start":{"column":1,"line":2}},"rule":"ICAL001","rule_name":"unknown-property","severity":"warning"}"#]].assert_eq(&lines);
+        let content = "BEGIN:VEVENT\r\nFOO:bar\r\nEND:VEVENT\r\n";
+        let diagnostics = document_diagnostics(None, content, &Config::default());
+        let log = sarif(&[("calendar.ics".to_owned(), diagnostics)]);
+        expect![[r#"
+            [
+              {
+                "level": "warning",
+                "locations": [
+                  {
+                    "physicalLocation": {
+                      "artifactLocation": {
+                        "uri": "calendar.ics"
+                      },
+                      "region": {
+                        "endColumn": 4,
+                        "endLine": 2,
+                        "startColumn": 1,
+                        "startLine": 2
+                      }
+                    }
+                  }
+                ],
+                "message": {
+                  "text": "Unknown property \"FOO\""
+                },
+                "ruleId": "ICAL001",
+                "ruleIndex": 0
+              }
+            ]"#]]
+        .assert_eq(&serde_json::to_string_pretty(&log["runs"][0]["results"]).unwrap());
+    }
+}
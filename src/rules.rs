@@ -0,0 +1,180 @@
+//! The rules that diagnostics are reported under, each with a stable ID.
+
+use lsp_types::{CodeDescription, Diagnostic, DiagnosticSeverity, NumberOrString, Range, Url};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter)]
+pub enum Rule {
+    UnknownProperty,
+    UnknownParameter,
+    InvalidPropertyValue,
+    InvalidParameterValue,
+    SyntaxError,
+    UnbalancedComponent,
+    EndWithDuration,
+    MismatchedTimeTypes,
+    EndBeforeStart,
+    UnknownTimezone,
+    TzidOnUtcTime,
+    UnusedTimezone,
+    LfLineEndings,
+    MixedLineEndings,
+    LongLine,
+    PropertyNotAllowed,
+}
+
+impl Rule {
+    /// The stable ID of the rule, e.g. `ICAL001`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Rule::UnknownProperty => "ICAL001",
+            Rule::UnknownParameter => "ICAL002",
+            Rule::InvalidPropertyValue => "ICAL003",
+            Rule::InvalidParameterValue => "ICAL004",
+            Rule::SyntaxError => "ICAL005",
+            Rule::UnbalancedComponent => "ICAL006",
+            Rule::EndWithDuration => "ICAL007",
+            Rule::MismatchedTimeTypes => "ICAL008",
+            Rule::EndBeforeStart => "ICAL009",
+            Rule::UnknownTimezone => "ICAL010",
+            Rule::TzidOnUtcTime => "ICAL011",
+            Rule::UnusedTimezone => "ICAL012",
+            Rule::LfLineEndings => "ICAL013",
+            Rule::MixedLineEndings => "ICAL014",
+            Rule::LongLine => "ICAL015",
+            Rule::PropertyNotAllowed => "ICAL016",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::UnknownProperty => "unknown-property",
+            Rule::UnknownParameter => "unknown-parameter",
+            Rule::InvalidPropertyValue => "invalid-property-value",
+            Rule::InvalidParameterValue => "invalid-parameter-value",
+            Rule::SyntaxError => "syntax-error",
+            Rule::UnbalancedComponent => "unbalanced-component",
+            Rule::EndWithDuration => "end-with-duration",
+            Rule::MismatchedTimeTypes => "mismatched-time-types",
+            Rule::EndBeforeStart => "end-before-start",
+            Rule::UnknownTimezone => "unknown-timezone",
+            Rule::TzidOnUtcTime => "tzid-on-utc-time",
+            Rule::UnusedTimezone => "unused-timezone",
+            Rule::LfLineEndings => "lf-line-endings",
+            Rule::MixedLineEndings => "mixed-line-endings",
+            Rule::LongLine => "long-line",
+            Rule::PropertyNotAllowed => "property-not-allowed",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Rule::UnknownProperty => "Property is not defined by RFC 5545 or in the config",
+            Rule::UnknownParameter => "Parameter is not defined by RFC 5545 or in the config",
+            Rule::InvalidPropertyValue => "Property value does not match its value type",
+            Rule::InvalidParameterValue => "Parameter value does not match its value type",
+            Rule::SyntaxError => "Content line is malformed",
+            Rule::UnbalancedComponent => "BEGIN without a matching END, or END without a BEGIN",
+            Rule::EndWithDuration => "DTEND or DUE given together with DURATION",
+            Rule::MismatchedTimeTypes => {
+                "DTEND or DUE has a different value type or time zone anchoring to DTSTART"
+            }
+            Rule::EndBeforeStart => "DTEND or DUE is not later than DTSTART",
+            Rule::UnknownTimezone => "TZID parameter without a matching VTIMEZONE",
+            Rule::TzidOnUtcTime => "TZID parameter on a UTC time",
+            Rule::UnusedTimezone => "VTIMEZONE that no TZID parameter refers to",
+            Rule::LfLineEndings => "Lines end with LF rather than CRLF",
+            Rule::MixedLineEndings => "Some lines end with LF rather than CRLF",
+            Rule::LongLine => "Line is longer than the folding width",
+            Rule::PropertyNotAllowed => "Property is given in a component it isn't allowed in",
+        }
+    }
+
+    pub fn default_severity(self) -> DiagnosticSeverity {
+        match self {
+            Rule::SyntaxError
+            | Rule::UnbalancedComponent
+            | Rule::EndWithDuration
+            | Rule::MismatchedTimeTypes
+            | Rule::EndBeforeStart
+            | Rule::UnknownTimezone
+            | Rule::TzidOnUtcTime => DiagnosticSeverity::ERROR,
+            Rule::UnknownProperty
+            | Rule::UnknownParameter
+            | Rule::InvalidPropertyValue
+            | Rule::InvalidParameterValue
+            | Rule::LfLineEndings
+            | Rule::MixedLineEndings
+            | Rule::PropertyNotAllowed => DiagnosticSeverity::WARNING,
+            Rule::LongLine => DiagnosticSeverity::INFORMATION,
+            Rule::UnusedTimezone => DiagnosticSeverity::HINT,
+        }
+    }
+
+    /// Where the rule is documented.
+    pub fn documentation(self) -> Url {
+        Url::parse(&format!(
+            "https://github.com/jeffa5/icalls/blob/main/docs/rules.md#{}-{}",
+            self.id().to_lowercase(),
+            self.name()
+        ))
+        .unwrap()
+    }
+
+    /// The rule with the given ID.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Rule::iter().find(|r| r.id() == id)
+    }
+
+    /// The rule with the given ID or name, as used in configuration.
+    pub fn from_key(key: &str) -> Option<Self> {
+        Rule::iter().find(|r| r.id().eq_ignore_ascii_case(key) || r.name() == key)
+    }
+
+    /// The rule a diagnostic was reported under.
+    pub fn of(diagnostic: &Diagnostic) -> Option<Self> {
+        match &diagnostic.code {
+            Some(NumberOrString::String(id)) => Self::from_id(id),
+            _ => None,
+        }
+    }
+
+    /// A diagnostic for this rule, with its code and default severity.
+    pub fn diagnostic(self, range: Range, message: String) -> Diagnostic {
+        Diagnostic {
+            range,
+            severity: Some(self.default_severity()),
+            code: Some(NumberOrString::String(self.id().to_owned())),
+            code_description: Some(CodeDescription {
+                href: self.documentation(),
+            }),
+            message,
+            ..Default::default()
+        }
+    }
+}
+
+/// The configured level of a rule, overriding its default severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    /// The severity to report diagnostics at, or `None` if the rule is disabled.
+    pub fn severity(self) -> Option<DiagnosticSeverity> {
+        match self {
+            Level::Off => None,
+            Level::Hint => Some(DiagnosticSeverity::HINT),
+            Level::Info => Some(DiagnosticSeverity::INFORMATION),
+            Level::Warning => Some(DiagnosticSeverity::WARNING),
+            Level::Error => Some(DiagnosticSeverity::ERROR),
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Semantic tokens for highlighting, derived from the ast rather than a grammar so that every
+//! client highlights the same way.
+
+use lsp_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType};
+use nom::InputTake as _;
+
+use crate::ast::{Document, Property, PropertyName, Span};
+use crate::diagnostics::span_range;
+use crate::value::ValueType;
+
+pub const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::new("escapeSequence"),
+];
+
+pub const TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+    SemanticTokenModifier::new("extension"),
+    SemanticTokenModifier::new("unknown"),
+];
+
+/// Indices into [`TOKEN_TYPES`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    Component,
+    Keyword,
+    Property,
+    Parameter,
+    String,
+    Number,
+    EnumMember,
+    Escape,
+}
+
+/// Bits of [`TOKEN_MODIFIERS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    None = 0,
+    Known = 1,
+    Extension = 2,
+    Unknown = 4,
+}
+
+/// A token on a single line, positioned in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub line: u32,
+    pub start: u32,
+    pub length: u32,
+    pub token_type: TokenType,
+    pub modifier: Modifier,
+}
+
+fn slice<'a>(span: Span<'a>, start: usize, end: usize) -> Span<'a> {
+    span.take_split(start).0.take(end - start)
+}
+
+/// Push a token for the span, splitting it at folds as tokens cannot span lines.
+fn push(span: Span, token_type: TokenType, modifier: Modifier, tokens: &mut Vec<Token>) {
+    let start = span_range(&span).start;
+    for (i, line) in span.fragment().split('\n').enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let (character, line) = if i == 0 {
+            (start.character, line)
+        } else {
+            // skip the whitespace that marks the fold
+            (1, line.get(1..).unwrap_or_default())
+        };
+        if line.is_empty() {
+            continue;
+        }
+        tokens.push(Token {
+            line: start.line + i as u32,
+            start: character,
+            length: line.chars().count() as u32,
+            token_type,
+            modifier,
+        });
+    }
+}
+
+fn name_modifier(name: &str, known: bool) -> Modifier {
+    if known {
+        Modifier::Known
+    } else if name.to_uppercase().starts_with("X-") {
+        Modifier::Extension
+    } else {
+        Modifier::Unknown
+    }
+}
+
+/// Push tokens for the runs of digits and letters in a value, e.g. `PT1H30M` or
+/// `20221008T170000Z`.
+fn push_runs(span: Span, letters: TokenType, tokens: &mut Vec<Token>) {
+    let fragment = span.fragment();
+    let mut run: Option<(usize, bool)> = None;
+    for (i, c) in fragment
+        .char_indices()
+        .chain(std::iter::once((fragment.len(), ' ')))
+    {
+        let kind = if c.is_ascii_digit() {
+            Some(true)
+        } else if c.is_alphabetic() {
+            Some(false)
+        } else {
+            None
+        };
+        if let Some((start, digits)) = run {
+            if kind == Some(digits) {
+                continue;
+            }
+            let token_type = if digits { TokenType::Number } else { letters };
+            push(slice(span, start, i), token_type, Modifier::None, tokens);
+        }
+        run = kind.map(|digits| (i, digits));
+    }
+}
+
+/// Push tokens for a TEXT value, highlighting escape sequences.
+fn push_text(span: Span, tokens: &mut Vec<Token>) {
+    let fragment = span.fragment();
+    let mut start = 0;
+    let mut chars = fragment.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+        let Some(&(j, escaped)) = chars.peek() else {
+            break;
+        };
+        chars.next();
+        push(
+            slice(span, start, i),
+            TokenType::String,
+            Modifier::None,
+            tokens,
+        );
+        let end = j + escaped.len_utf8();
+        push(
+            slice(span, i, end),
+            TokenType::Escape,
+            Modifier::None,
+            tokens,
+        );
+        start = end;
+    }
+    push(
+        slice(span, start, fragment.len()),
+        TokenType::String,
+        Modifier::None,
+        tokens,
+    );
+}
+
+/// Push tokens for a RECUR value, with the rule part names as keywords.
+fn push_rule(span: Span, tokens: &mut Vec<Token>) {
+    let mut offset = 0;
+    for part in span.fragment().split(';') {
+        let part_span = slice(span, offset, offset + part.len());
+        match part.split_once('=') {
+            Some((name, _)) => {
+                push(
+                    slice(part_span, 0, name.len()),
+                    TokenType::Keyword,
+                    Modifier::None,
+                    tokens,
+                );
+                push_runs(
+                    slice(part_span, name.len() + 1, part.len()),
+                    TokenType::EnumMember,
+                    tokens,
+                );
+            }
+            None => push(part_span, TokenType::String, Modifier::None, tokens),
+        }
+        offset += part.len() + 1;
+    }
+}
+
+fn property_tokens(property: &Property, tokens: &mut Vec<Token>) {
+    let structural = matches!(property.name, Some(PropertyName::Begin | PropertyName::End));
+    let name_type = if structural {
+        TokenType::Keyword
+    } else {
+        TokenType::Property
+    };
+    let modifier = name_modifier(property.name_raw.fragment(), property.name.is_some());
+    push(property.name_raw, name_type, modifier, tokens);
+
+    for param in &property.params {
+        let modifier = name_modifier(param.name_raw.fragment(), param.name.is_some());
+        push(param.name_raw, TokenType::Parameter, modifier, tokens);
+        if let Some(value) = param.value {
+            push(value, TokenType::String, Modifier::None, tokens);
+        }
+    }
+
+    let Some(value) = property.value else {
+        return;
+    };
+    if structural {
+        push(value, TokenType::Component, Modifier::None, tokens);
+        return;
+    }
+    match property.value_type() {
+        Some(
+            ValueType::Date
+            | ValueType::DateTime
+            | ValueType::Time
+            | ValueType::Duration
+            | ValueType::PeriodOfTime
+            | ValueType::UtcOffset,
+        ) => push_runs(value, TokenType::Keyword, tokens),
+        Some(ValueType::RecurrenceRule) => push_rule(value, tokens),
+        Some(ValueType::Integer | ValueType::Float) => {
+            push(value, TokenType::Number, Modifier::None, tokens)
+        }
+        Some(ValueType::Boolean) => push(value, TokenType::Keyword, Modifier::None, tokens),
+        Some(ValueType::Text) => push_text(value, tokens),
+        Some(ValueType::Binary | ValueType::CalAddress | ValueType::Uri) | None => {
+            push(value, TokenType::String, Modifier::None, tokens)
+        }
+    }
+}
+
+/// The tokens of a document, in document order.
+pub fn tokens(document: &Document) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for property in &document.properties {
+        property_tokens(property, &mut tokens);
+    }
+    tokens
+}
+
+/// Encode tokens relative to each other, as sent to the client.
+pub fn encode(tokens: &[Token]) -> Vec<SemanticToken> {
+    let mut previous = (0, 0);
+    tokens
+        .iter()
+        .map(|token| {
+            let delta_line = token.line - previous.0;
+            let delta_start = if delta_line == 0 {
+                token.start - previous.1
+            } else {
+                token.start
+            };
+            previous = (token.line, token.start);
+            SemanticToken {
+                delta_line,
+                delta_start,
+                length: token.length,
+                token_type: token.token_type as u32,
+                token_modifiers_bitset: token.modifier as u32,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::ast::parse_document;
+
+    #[test]
+    fn document_tokens() {
+        let document = parse_document(Span::new(
+            "BEGIN:VEVENT
+DTSTART;TZID=Europe/London:20221008T170000
+DURATION:PT1H30M
+RRULE:FREQ=WEEKLY;BYDAY=-1FR
+SUMMARY:Party\\, with
+  escapes
+X-FOO;FOO=bar:baz
+END:VEVENT
+",
+        ));
+        let tokens = tokens(&document)
+            .iter()
+            .map(|t| {
+                format!(
+                    "{}:{} {} {:?} {:?}",
+                    t.line, t.start, t.length, t.token_type, t.modifier
+                )
+            })
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                "0:0 5 Keyword Known",
+                "0:6 6 Component None",
+                "1:0 7 Property Known",
+                "1:8 4 Parameter Known",
+                "1:13 13 String None",
+                "1:27 8 Number None",
+                "1:35 1 Keyword None",
+                "1:36 6 Number None",
+                "2:0 8 Property Known",
+                "2:9 2 Keyword None",
+                "2:11 1 Number None",
+                "2:12 1 Keyword None",
+                "2:13 2 Number None",
+                "2:15 1 Keyword None",
+                "3:0 5 Property Known",
+                "3:6 4 Keyword None",
+                "3:11 6 EnumMember None",
+                "3:18 5 Keyword None",
+                "3:25 1 Number None",
+                "3:26 2 EnumMember None",
+                "4:0 7 Property Known",
+                "4:8 5 String None",
+                "4:13 2 Escape None",
+                "4:15 5 String None",
+                "5:1 8 String None",
+                "6:0 5 Property Extension",
+                "6:6 3 Parameter Unknown",
+                "6:10 3 String None",
+                "6:14 3 String None",
+                "7:0 3 Keyword Known",
+                "7:4 6 Component None",
+            ]
+        "#]]
+        .assert_debug_eq(&tokens);
+    }
+}
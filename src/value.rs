@@ -1,4 +1,11 @@
-#[derive(Debug)]
+use std::str::FromStr;
+
+use chrono::{Datelike as _, NaiveDate};
+
+use crate::recur::RecurrenceRule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum ValueType {
     Binary,
     Boolean,
@@ -16,6 +23,66 @@ pub enum ValueType {
     UtcOffset,
 }
 
+impl FromStr for ValueType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "binary" => Ok(Self::Binary),
+            "boolean" => Ok(Self::Boolean),
+            "cal-address" => Ok(Self::CalAddress),
+            "date" => Ok(Self::Date),
+            "date-time" => Ok(Self::DateTime),
+            "duration" => Ok(Self::Duration),
+            "float" => Ok(Self::Float),
+            "integer" => Ok(Self::Integer),
+            "period" => Ok(Self::PeriodOfTime),
+            "recur" => Ok(Self::RecurrenceRule),
+            "text" => Ok(Self::Text),
+            "time" => Ok(Self::Time),
+            "uri" => Ok(Self::Uri),
+            "utc-offset" => Ok(Self::UtcOffset),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<String> for ValueType {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s).map_err(|()| format!("unknown value type {s:?}"))
+    }
+}
+
+impl From<ValueType> for String {
+    fn from(value_type: ValueType) -> Self {
+        value_type.name().to_owned()
+    }
+}
+
+impl ValueType {
+    /// The name used in `VALUE` parameters, e.g. `DATE-TIME`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Binary => "BINARY",
+            Self::Boolean => "BOOLEAN",
+            Self::CalAddress => "CAL-ADDRESS",
+            Self::Date => "DATE",
+            Self::DateTime => "DATE-TIME",
+            Self::Duration => "DURATION",
+            Self::Float => "FLOAT",
+            Self::Integer => "INTEGER",
+            Self::PeriodOfTime => "PERIOD",
+            Self::RecurrenceRule => "RECUR",
+            Self::Text => "TEXT",
+            Self::Time => "TIME",
+            Self::Uri => "URI",
+            Self::UtcOffset => "UTC-OFFSET",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Value {
     Binary(Vec<u8>),
@@ -23,11 +90,11 @@ pub enum Value {
     CalAddress(String),
     Date(Date),
     DateTime(Date, Time),
-    Duration(String),
+    Duration(Duration),
     Float(f64),
     Integer(i64),
     PeriodOfTime(String),
-    RecurrenceRule(String),
+    RecurrenceRule(Box<RecurrenceRule>),
     Text(String),
     Time(Time),
     Uri(String),
@@ -42,20 +109,45 @@ impl Value {
             Value::CalAddress(v) => v.to_string(),
             Value::Date(date) => date.prettify(),
             Value::DateTime(d, t) => format!("{} {}", t.prettify(), d.prettify()),
-            Value::Duration(v) => v.to_string(),
+            Value::Duration(v) => v.prettify(),
             Value::Float(v) => v.to_string(),
             Value::Integer(v) => v.to_string(),
             Value::PeriodOfTime(v) => v.to_string(),
-            Value::RecurrenceRule(v) => v.to_string(),
+            Value::RecurrenceRule(v) => v.describe(),
             Value::Text(v) => v.to_string(),
             Value::Time(v) => v.prettify(),
             Value::Uri(v) => v.to_string(),
-            Value::UtcOffset(v) => v.to_string(),
+            Value::UtcOffset(v) => pretty_utc_offset(v),
         }
     }
 }
 
-#[derive(Debug)]
+/// Format a UTC offset like `-0500` as `UTC-05:00`.
+fn pretty_utc_offset(v: &str) -> String {
+    match (v.get(..3), v.get(3..5), v.get(5..)) {
+        (Some(hours), Some(minutes), Some("")) => format!("UTC{hours}:{minutes}"),
+        (Some(hours), Some(minutes), Some(seconds)) => format!("UTC{hours}:{minutes}:{seconds}"),
+        _ => v.to_string(),
+    }
+}
+
+/// Escape a TEXT value so that it can be written out in a content line.
+pub fn escape_text(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Date {
     pub year: u16,
     pub month: u8,
@@ -63,6 +155,24 @@ pub struct Date {
 }
 
 impl Date {
+    pub fn to_naive(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), self.day.into())
+    }
+
+    /// The day of the week, if the date exists.
+    pub fn weekday(&self) -> Option<Weekday> {
+        Some(self.to_naive()?.weekday().into())
+    }
+
+    /// A short form of the date, e.g. `Sat 8 Oct 2022`.
+    pub fn short(&self) -> String {
+        let date = format!("{} {} {}", self.day, short_month(self.month), self.year);
+        match self.weekday() {
+            Some(weekday) => format!("{} {date}", weekday.short()),
+            None => date,
+        }
+    }
+
     fn prettify(&self) -> String {
         format!(
             "{} {} {}",
@@ -73,6 +183,12 @@ impl Date {
     }
 }
 
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+}
+
 fn pretty_day(d: u8) -> String {
     if matches!(d, 1 | 21 | 31) {
         format!("{}st", d)
@@ -85,8 +201,12 @@ fn pretty_day(d: u8) -> String {
     }
 }
 
+pub(crate) fn short_month(m: u8) -> &'static str {
+    &pretty_month(m)[..3]
+}
+
 fn pretty_month(m: u8) -> &'static str {
-    [
+    const MONTHS: [&str; 12] = [
         "January",
         "February",
         "March",
@@ -99,10 +219,52 @@ fn pretty_month(m: u8) -> &'static str {
         "October",
         "November",
         "December",
-    ][m as usize]
+    ];
+    MONTHS
+        .get((m as usize).wrapping_sub(1))
+        .unwrap_or(&"unknown month")
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub fn short(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "Mon",
+            Weekday::Tuesday => "Tue",
+            Weekday::Wednesday => "Wed",
+            Weekday::Thursday => "Thu",
+            Weekday::Friday => "Fri",
+            Weekday::Saturday => "Sat",
+            Weekday::Sunday => "Sun",
+        }
+    }
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(weekday: chrono::Weekday) -> Self {
+        match weekday {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Time {
     pub hour: u8,
     pub minute: u8,
@@ -110,8 +272,194 @@ pub struct Time {
     pub utc: bool,
 }
 
+/// Convert a date-time in UTC, or in the time zone `tzid`, to the time zone `to`.
+///
+/// Local times aren't in any time zone so can't be converted, nor can those in time zones not in the
+/// IANA database.
+pub fn convert_timezone(
+    date: &Date,
+    time: &Time,
+    tzid: Option<&str>,
+    to: chrono_tz::Tz,
+) -> Option<(Date, Time)> {
+    use chrono::{Datelike as _, TimeZone as _, Timelike as _};
+
+    let naive =
+        chrono::NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())?
+            .and_hms_opt(time.hour.into(), time.minute.into(), time.second.into())?;
+    let converted = if time.utc {
+        chrono::Utc.from_utc_datetime(&naive).with_timezone(&to)
+    } else {
+        let from = chrono_tz::Tz::from_str(tzid?).ok()?;
+        from.from_local_datetime(&naive)
+            .earliest()?
+            .with_timezone(&to)
+    };
+    Some((
+        Date {
+            year: converted.year() as u16,
+            month: converted.month() as u8,
+            day: converted.day() as u8,
+        },
+        Time {
+            hour: converted.hour() as u8,
+            minute: converted.minute() as u8,
+            second: converted.second() as u8,
+            utc: false,
+        },
+    ))
+}
+
 impl Time {
     fn prettify(&self) -> String {
         format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
     }
+
+    /// A short form of the time, leaving off the seconds when there are none, e.g. `17:00`.
+    pub fn short(&self) -> String {
+        if self.second == 0 {
+            format!("{:02}:{:02}", self.hour, self.minute)
+        } else {
+            self.prettify()
+        }
+    }
+}
+
+impl std::fmt::Display for Time {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}{:02}{:02}", self.hour, self.minute, self.second)?;
+        if self.utc {
+            write!(f, "Z")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Duration {
+    pub negative: bool,
+    pub weeks: u32,
+    pub days: u32,
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+}
+
+impl FromStr for Duration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, rest) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let Some(rest) = rest.strip_prefix('P') else {
+            return Err("Did not start with 'P'".to_owned());
+        };
+        let mut duration = Duration {
+            negative,
+            ..Default::default()
+        };
+        let (date, time) = match rest.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (rest, None),
+        };
+        let mut units = Vec::new();
+        for (part, allowed) in [(date, "WD"), (time.unwrap_or_default(), "HMS")] {
+            let mut number = String::new();
+            for c in part.chars() {
+                if c.is_ascii_digit() {
+                    number.push(c);
+                    continue;
+                }
+                if !allowed.contains(c) {
+                    return Err(format!("Unexpected {c:?}"));
+                }
+                let n = number
+                    .parse()
+                    .map_err(|_| format!("Missing number before {c:?}"))?;
+                number.clear();
+                units.push(c);
+                match c {
+                    'W' => duration.weeks = n,
+                    'D' => duration.days = n,
+                    'H' => duration.hours = n,
+                    'M' => duration.minutes = n,
+                    _ => duration.seconds = n,
+                }
+            }
+            if !number.is_empty() {
+                return Err(format!("Missing unit after {number:?}"));
+            }
+        }
+        let order = "WDHMS";
+        if !units
+            .windows(2)
+            .all(|w| order.find(w[0]) < order.find(w[1]))
+        {
+            return Err("Units are not in the order W, D, H, M, S".to_owned());
+        }
+        if units.contains(&'W') && units.len() > 1 {
+            return Err("Weeks cannot be combined with other units".to_owned());
+        }
+        if time.is_some_and(|t| t.is_empty()) {
+            return Err("Missing time after 'T'".to_owned());
+        }
+        if units.is_empty() {
+            return Err("Missing duration".to_owned());
+        }
+        Ok(duration)
+    }
+}
+
+impl Duration {
+    pub fn as_seconds(&self) -> i64 {
+        let seconds = i64::from(self.weeks) * 7 * 24 * 60 * 60
+            + i64::from(self.days) * 24 * 60 * 60
+            + i64::from(self.hours) * 60 * 60
+            + i64::from(self.minutes) * 60
+            + i64::from(self.seconds);
+        if self.negative {
+            -seconds
+        } else {
+            seconds
+        }
+    }
+
+    fn parts(&self) -> Vec<(u32, &'static str, &'static str)> {
+        [
+            (self.weeks, "w", "week"),
+            (self.days, "d", "day"),
+            (self.hours, "h", "hour"),
+            (self.minutes, "m", "minute"),
+            (self.seconds, "s", "second"),
+        ]
+        .into_iter()
+        .filter(|(n, _, _)| *n != 0)
+        .collect()
+    }
+
+    /// A compact form of the duration, e.g. `1h 30m`.
+    pub fn prettify(&self) -> String {
+        let parts = self.parts();
+        if parts.is_empty() {
+            return "0s".to_owned();
+        }
+        let sign = if self.negative { "-" } else { "" };
+        let parts = parts
+            .iter()
+            .map(|(n, unit, _)| format!("{n}{unit}"))
+            .collect::<Vec<_>>();
+        format!("{sign}{}", parts.join(" "))
+    }
+
+    /// A long form of the duration without the sign, e.g. `1 hour 30 minutes`.
+    pub fn describe(&self) -> String {
+        self.parts()
+            .iter()
+            .map(|(n, _, unit)| format!("{n} {unit}{}", if *n == 1 { "" } else { "s" }))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }